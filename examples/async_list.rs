@@ -1,5 +1,5 @@
 use dioxus::prelude::*;
-use dioxus_lazy::{lazy, List};
+use dioxus_lazy::{lazy, List, ListItem, Overscan};
 use dioxus_logger::tracing::Level;
 
 fn app() -> Element {
@@ -8,8 +8,11 @@ fn app() -> Element {
             len: 100,
             size: 400.,
             item_size: 20.,
-            make_item: move |idx: &usize| rsx! { "Async item {*idx}" },
-            make_value: lazy::from_async_fn(|idx| async move { idx })
+            overscan: Overscan::Items(5),
+            overscan_ahead: Overscan::Pixels(200.),
+            make_item: move |item: ListItem<usize>| rsx! { "Async item {item.value}" },
+            make_placeholder: move |idx: usize| rsx! { "Loading item {idx}..." },
+            make_value: lazy::from_async_fn_buffered(10, |idx| async move { idx })
         }
     }
 }
@@ -0,0 +1,32 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, List, ListItem, Size};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    rsx! {
+        div {
+            display: "flex",
+            flex_direction: "column",
+            height: "100vh",
+            h1 { "Header" }
+            div {
+                flex: "1",
+                min_height: "0",
+                List {
+                    len: 100,
+                    size: Size::Auto,
+                    item_size: 20.,
+                    make_item: move |item: ListItem<usize>| rsx! { "Item {item.value}" },
+                    make_value: lazy::from_fn(|idx| { idx })
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
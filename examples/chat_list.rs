@@ -0,0 +1,51 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, List, ListItem};
+use dioxus_logger::tracing::Level;
+use std::collections::VecDeque;
+
+fn app() -> Element {
+    let mut messages = use_signal(|| {
+        (0..50)
+            .map(|idx| format!("Message {idx}"))
+            .collect::<VecDeque<_>>()
+    });
+    let mut origin = use_signal(|| 0);
+    let mut oldest = use_signal(|| 0);
+
+    rsx! {
+        button {
+            onclick: move |_| {
+                for _ in 0..20 {
+                    oldest -= 1;
+                    messages.write().push_front(format!("Message {oldest}"));
+                }
+                origin += 20;
+            },
+            "Load older"
+        }
+        button {
+            onclick: move |_| {
+                let idx = messages.read().len() as i32 + oldest();
+                messages.write().push_back(format!("Message {idx}"));
+            },
+            "Send"
+        }
+        List {
+            len: messages.read().len(),
+            origin: origin(),
+            stick_to_end: true,
+            size: 400.,
+            item_size: 20.,
+            make_item: move |item: ListItem<String>| rsx! { "{item.value}" },
+            key_fn: |message: &String, _| message.clone(),
+            make_value: lazy::from_fn(move |idx| messages.read()[idx].clone())
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
@@ -0,0 +1,32 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, List, ListError, ListItem};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    rsx! {
+        List {
+            len: 100,
+            size: 400.,
+            item_size: 20.,
+            make_item: move |item: ListItem<usize>| rsx! { "Item {item.value}" },
+            make_value: lazy::try_from_async_fn(|idx| async move {
+                if idx % 25 == 24 {
+                    Err(format!("Failed to load item {idx}"))
+                } else {
+                    Ok(idx)
+                }
+            }),
+            make_error: move |error: ListError<String>| rsx! {
+                "{error.error}"
+                button { onclick: move |_| error.retry.call(()), "Retry" }
+            }
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
@@ -0,0 +1,25 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, Grid};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    rsx! {
+        Grid {
+            len: 10_000,
+            columns: 100,
+            width: 400.,
+            height: 400.,
+            cell_width: 80.,
+            cell_height: 80.,
+            make_item: move |idx: &usize| rsx! { "Cell {*idx}" },
+            make_value: lazy::from_async_fn(|idx| async move { idx })
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
@@ -0,0 +1,23 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, Direction, List, ListItem};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    rsx! {
+        List {
+            len: 100,
+            direction: Direction::Column,
+            size: 400.,
+            item_size: 80.,
+            make_item: move |item: ListItem<usize>| rsx! { "Item {item.value}" },
+            make_value: lazy::from_fn(|idx| { idx })
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
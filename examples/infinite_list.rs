@@ -0,0 +1,26 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, List, ListItem};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    let mut len = use_signal(|| 50);
+
+    rsx! {
+        List {
+            len: len(),
+            size: 400.,
+            item_size: 20.,
+            end_threshold: 100.,
+            on_end_reached: move |_| len += 50,
+            make_item: move |item: ListItem<usize>| rsx! { "Item {item.value}" },
+            make_value: lazy::from_async_fn(|idx| async move { idx })
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
@@ -1,5 +1,5 @@
 use dioxus::prelude::*;
-use dioxus_lazy::{lazy, List};
+use dioxus_lazy::{lazy, List, ListItem};
 use dioxus_logger::tracing::Level;
 
 fn app() -> Element {
@@ -8,7 +8,12 @@ fn app() -> Element {
             len: 100,
             size: 400.,
             item_size: 20.,
-            make_item: move |idx: &usize| rsx! { "Item {*idx}" },
+            make_item: move |item: ListItem<usize>| rsx! {
+                div {
+                    background: if item.idx.is_multiple_of(2) { "#eee" } else { "#fff" },
+                    "Item {item.value}"
+                }
+            },
             make_value: lazy::from_fn(|idx| { idx })
         }
     }
@@ -0,0 +1,25 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, List, ListItem};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    rsx! {
+        List {
+            len: 100,
+            size: 400.,
+            item_size: 20.,
+            measure_items: true,
+            make_item: move |item: ListItem<usize>| rsx! {
+                div { height: "{20 + (item.value % 5) * 10}px", "Item {item.value}" }
+            },
+            make_value: lazy::from_fn(|idx| { idx })
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
@@ -0,0 +1,49 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{factory, lazy, Cache, List, ListItem};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    let cache = use_context_provider(|| Cache::<String>::new(500));
+    let mut version = use_context_provider(|| Signal::new(0));
+
+    rsx! {
+        button {
+            onclick: move |_| {
+                version += 1;
+                cache.invalidate(0..10);
+            },
+            "Reload the first 10 items"
+        }
+        div { display: "flex",
+            SharedList { size: 200. }
+            SharedList { size: 400. }
+        }
+    }
+}
+
+#[component]
+fn SharedList(size: f64) -> Element {
+    let cache = use_context::<Cache<String>>();
+    let version = use_context::<Signal<i32>>();
+
+    rsx! {
+        List {
+            len: 1000,
+            size,
+            item_size: 20.,
+            make_item: move |item: ListItem<String>| rsx! { "{item.value}" },
+            make_placeholder: move |idx: usize| rsx! { "Loading item {idx}..." },
+            make_value: lazy::from_factory(factory::with_cache(
+                cache.clone(),
+                factory::from_fn(move |idx| async move { format!("Item {idx} (v{})", version.peek()) }),
+            ))
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
@@ -0,0 +1,35 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, List, ListItem};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    let mut items = use_signal(|| {
+        (0..100)
+            .map(|idx| format!("Item {idx}"))
+            .collect::<Vec<_>>()
+    });
+
+    rsx! {
+        button {
+            onclick: move |_| {
+                let len = items.read().len();
+                items.write().push(format!("Item {len}"));
+            },
+            "Add"
+        }
+        button { onclick: move |_| items.write().clear(), "Clear" }
+        List {
+            size: 400.,
+            item_size: 20.,
+            make_item: move |item: ListItem<String>| rsx! { "{item.value}" },
+            make_value: lazy::from_signal(items)
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
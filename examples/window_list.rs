@@ -0,0 +1,23 @@
+use dioxus::prelude::*;
+use dioxus_lazy::{lazy, List, ListItem};
+use dioxus_logger::tracing::Level;
+
+fn app() -> Element {
+    rsx! {
+        h1 { "Search results" }
+        List {
+            len: 10_000,
+            item_size: 20.,
+            window_scroll: true,
+            make_item: move |item: ListItem<usize>| rsx! { "Result {item.value}" },
+            make_value: lazy::from_fn(|idx| { idx })
+        }
+    }
+}
+
+fn main() {
+    dioxus_logger::init(Level::INFO).unwrap();
+    console_error_panic_hook::set_once();
+
+    dioxus::launch(app);
+}
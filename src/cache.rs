@@ -0,0 +1,358 @@
+use futures::{
+    channel::oneshot,
+    future::{FutureExt, Shared},
+};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+    rc::{Rc, Weak},
+};
+
+/// Future that completes once an index being loaded is in the cache, or failed to load.
+pub(crate) type Pending = Shared<oneshot::Receiver<()>>;
+
+type Listener = Rc<dyn Fn(Range<usize>)>;
+
+/// Bounded cache of values by index, evicting the least recently used values first.
+///
+/// Clones share the same values, so a cache can be shared by several lists,
+/// for example by providing it as context:
+///
+/// ```rust,ignore
+/// let cache = use_context_provider(|| Cache::new(500));
+/// let make_value = factory::with_cache(cache, factory::from_fn(|idx| async move { idx }));
+/// ```
+///
+/// Indices being loaded by one list are awaited by the others instead of fetched again,
+/// and [`Cache::invalidate`] reloads them in every list.
+pub struct Cache<V> {
+    inner: Rc<RefCell<Inner<V>>>,
+}
+
+struct Inner<V> {
+    capacity: usize,
+    tick: u64,
+    values: HashMap<usize, (V, u64)>,
+    order: BTreeMap<u64, usize>,
+    next_id: u64,
+    pending: HashMap<usize, (u64, Pending)>,
+    listeners: HashMap<u64, Listener>,
+}
+
+impl<V> Cache<V> {
+    /// Create a new cache holding up to `capacity` values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(Inner {
+                capacity,
+                tick: 0,
+                values: HashMap::new(),
+                order: BTreeMap::new(),
+                next_id: 0,
+                pending: HashMap::new(),
+                listeners: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Get the maximum number of values.
+    pub fn capacity(&self) -> usize {
+        self.inner.borrow().capacity
+    }
+
+    /// Get the number of values.
+    pub fn len(&self) -> usize {
+        self.inner.borrow().values.len()
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().values.is_empty()
+    }
+
+    /// Get the value at `idx`, marking it as the most recently used.
+    pub fn get(&self, idx: usize) -> Option<V>
+    where
+        V: Clone,
+    {
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+        let (value, tick) = inner.values.get_mut(&idx)?;
+
+        inner.order.remove(tick);
+        inner.tick += 1;
+        *tick = inner.tick;
+        inner.order.insert(inner.tick, idx);
+
+        Some(value.clone())
+    }
+
+    /// Insert the `value` at `idx`, evicting the least recently used value when full.
+    pub fn insert(&self, idx: usize, value: V) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.capacity == 0 {
+            return;
+        }
+
+        if let Some((_, tick)) = inner.values.remove(&idx) {
+            inner.order.remove(&tick);
+        } else if inner.values.len() >= inner.capacity {
+            if let Some((_, evicted)) = inner.order.pop_first() {
+                inner.values.remove(&evicted);
+            }
+        }
+
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.values.insert(idx, (value, tick));
+        inner.order.insert(tick, idx);
+    }
+
+    /// Remove every value with an index in `range` and reload them in every list using this cache.
+    pub fn invalidate(&self, range: Range<usize>) {
+        self.invalidate_from(range, None);
+    }
+
+    /// Remove every value.
+    pub fn clear(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.values.clear();
+        inner.order.clear();
+        inner.pending.clear();
+    }
+
+    /// Remove every value with an index in `range`, without notifying listeners.
+    ///
+    /// Values still loading for these indices won't be inserted.
+    pub(crate) fn remove_range(&self, range: Range<usize>) {
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+        inner.values.retain(|idx, (_, tick)| {
+            let is_removed = range.contains(idx);
+            if is_removed {
+                inner.order.remove(tick);
+            }
+            !is_removed
+        });
+        inner.pending.retain(|idx, _| !range.contains(idx));
+    }
+
+    /// Remove every value with an index in `range` and notify every listener but the `subscriber`'s.
+    pub(crate) fn invalidate_from(&self, range: Range<usize>, subscriber: Option<u64>) {
+        self.remove_range(range.clone());
+
+        let listeners: Vec<_> = self
+            .inner
+            .borrow()
+            .listeners
+            .iter()
+            .filter(|(id, _)| Some(**id) != subscriber)
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            listener(range.clone());
+        }
+    }
+
+    /// Get the future of the load in progress for `idx`, if any.
+    pub(crate) fn pending(&self, idx: usize) -> Option<Pending> {
+        self.inner
+            .borrow()
+            .pending
+            .get(&idx)
+            .map(|(_, pending)| pending.clone())
+    }
+
+    /// Mark the indices in `range` as loading, until the returned guard is dropped.
+    pub(crate) fn load(&self, range: Range<usize>) -> Loading<V> {
+        let (tx, rx) = oneshot::channel();
+        let pending = rx.shared();
+
+        let mut inner = self.inner.borrow_mut();
+        inner.next_id += 1;
+        let id = inner.next_id;
+        for idx in range.clone() {
+            inner.pending.insert(idx, (id, pending.clone()));
+        }
+
+        Loading {
+            cache: self.clone(),
+            id,
+            range,
+            _tx: tx,
+        }
+    }
+
+    /// Call `listener` with every invalidated range, until the returned subscription is dropped.
+    pub(crate) fn subscribe(&self, listener: Listener) -> Subscription
+    where
+        V: 'static,
+    {
+        let mut inner = self.inner.borrow_mut();
+        inner.next_id += 1;
+        let id = inner.next_id;
+        inner.listeners.insert(id, listener);
+
+        let weak = Rc::downgrade(&self.inner);
+        Subscription {
+            id,
+            unsubscribe: Some(Box::new(move || {
+                if let Some(inner) = Weak::upgrade(&weak) {
+                    inner.borrow_mut().listeners.remove(&id);
+                }
+            })),
+        }
+    }
+}
+
+/// Guard for indices being loaded into a [`Cache`], waking anyone waiting on them when dropped.
+pub(crate) struct Loading<V> {
+    cache: Cache<V>,
+    id: u64,
+    range: Range<usize>,
+    _tx: oneshot::Sender<()>,
+}
+
+impl<V> Loading<V> {
+    /// Insert the loaded `value` at `idx`, unless it was invalidated while loading.
+    pub(crate) fn insert(&self, idx: usize, value: V) {
+        let is_current = matches!(
+            self.cache.inner.borrow().pending.get(&idx),
+            Some((id, _)) if *id == self.id
+        );
+        if is_current {
+            self.cache.insert(idx, value);
+        }
+    }
+}
+
+impl<V> Drop for Loading<V> {
+    fn drop(&mut self) {
+        let mut inner = self.cache.inner.borrow_mut();
+        inner
+            .pending
+            .retain(|idx, (id, _)| *id != self.id || !self.range.contains(idx));
+    }
+}
+
+/// Subscription to a [`Cache`]'s invalidations, removed when dropped.
+pub struct Subscription {
+    id: u64,
+    unsubscribe: Option<Box<dyn FnOnce()>>,
+}
+
+impl Subscription {
+    /// Get the id of this subscription's listener.
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe()
+        }
+    }
+}
+
+impl<V> Clone for Cache<V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<V> PartialEq for Cache<V> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cache;
+    use std::{cell::RefCell, ops::Range, rc::Rc};
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = Cache::new(2);
+        cache.insert(0, "a");
+        cache.insert(1, "b");
+
+        // Reading marks the value as used, so the other one is evicted.
+        assert_eq!(cache.get(0), Some("a"));
+        cache.insert(2, "c");
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.get(0), Some("a"));
+        assert_eq!(cache.get(2), Some("c"));
+    }
+
+    #[test]
+    fn replacing_a_value_does_not_evict() {
+        let cache = Cache::new(2);
+        cache.insert(0, "a");
+        cache.insert(1, "b");
+        cache.insert(0, "c");
+        assert_eq!(cache.len(), 2);
+
+        // The replaced value was used last, so the other one is evicted first.
+        cache.insert(2, "d");
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.get(0), Some("c"));
+        assert_eq!(cache.get(2), Some("d"));
+    }
+
+    #[test]
+    fn zero_capacity_keeps_nothing() {
+        let cache = Cache::new(0);
+        cache.insert(0, "a");
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(0), None);
+    }
+
+    #[test]
+    fn invalidate_removes_range_and_notifies() {
+        let cache = Cache::new(10);
+        for idx in 0..5 {
+            cache.insert(idx, idx);
+        }
+
+        let ranges = Rc::new(RefCell::new(Vec::new()));
+        let ranges_clone = ranges.clone();
+        let subscription = cache.subscribe(Rc::new(move |range| {
+            ranges_clone.borrow_mut().push(range);
+        }));
+
+        cache.invalidate(1..3);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.get(3), Some(3));
+        assert_eq!(ranges.borrow().clone(), vec![Range { start: 1, end: 3 }]);
+
+        drop(subscription);
+        cache.invalidate(0..1);
+        assert_eq!(ranges.borrow().len(), 1);
+    }
+
+    #[test]
+    fn loads_invalidated_while_pending_are_dropped() {
+        let cache = Cache::new(10);
+        let loading = cache.load(0..2);
+        assert!(cache.pending(1).is_some());
+
+        cache.remove_range(1..2);
+        loading.insert(0, "a");
+        loading.insert(1, "b");
+        assert_eq!(cache.get(0), Some("a"));
+        assert_eq!(cache.get(1), None);
+
+        drop(loading);
+        assert!(cache.pending(0).is_none());
+    }
+}
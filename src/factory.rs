@@ -1,5 +1,12 @@
-use futures::Future;
-use std::{ops::Range, pin::Pin};
+use crate::{
+    cache::{Loading, Pending},
+    Cache, Subscription,
+};
+use futures::{
+    future::{self, Map},
+    stream, Future, FutureExt, StreamExt,
+};
+use std::{cell::Cell, convert::Infallible, ops::Range, pin::Pin, rc::Rc};
 
 pub trait Factory {
     type Item;
@@ -9,6 +16,41 @@ pub trait Factory {
     fn make(&self, range: Range<usize>, is_rev: bool) -> Self::Future;
 }
 
+/// Fallible factory, implemented for every [`Factory`] that can't fail.
+pub trait TryFactory {
+    type Item;
+    type Error;
+    type Output: IntoIterator<Item = Self::Item>;
+    type Future: Future<Output = Result<Self::Output, Self::Error>>;
+
+    fn try_make(&self, range: Range<usize>, is_rev: bool) -> Self::Future;
+
+    /// Forget any values kept for indices in `range`, so they're made again.
+    fn invalidate(&self, range: Range<usize>) {
+        let _ = range;
+    }
+
+    /// Call `listener` with every range invalidated by another user of these values, if they're shared.
+    fn subscribe(&self, listener: Rc<dyn Fn(Range<usize>)>) -> Option<Subscription> {
+        let _ = listener;
+        None
+    }
+}
+
+impl<F> TryFactory for F
+where
+    F: Factory,
+{
+    type Item = F::Item;
+    type Error = Infallible;
+    type Output = F::Output;
+    type Future = Map<F::Future, fn(F::Output) -> Result<F::Output, Infallible>>;
+
+    fn try_make(&self, range: Range<usize>, is_rev: bool) -> Self::Future {
+        self.make(range, is_rev).map(Ok)
+    }
+}
+
 pub fn from_fn<F, Fut, V>(f: F) -> FromFn<F>
 where
     F: Fn(usize) -> Fut + Clone + 'static,
@@ -53,6 +95,49 @@ where
     }
 }
 
+/// Make each value with `f`, running up to `limit` calls at once.
+///
+/// Values are still returned in order.
+pub fn from_fn_buffered<F, Fut, V>(limit: usize, f: F) -> FromFnBuffered<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = V> + 'static,
+    V: 'static,
+{
+    FromFnBuffered { f, limit }
+}
+
+#[derive(Clone, Copy)]
+pub struct FromFnBuffered<F> {
+    f: F,
+    limit: usize,
+}
+
+impl<F, Fut, V> Factory for FromFnBuffered<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = V> + 'static,
+    V: 'static,
+{
+    type Item = V;
+    type Output = std::vec::IntoIter<V>;
+    type Future = Pin<Box<dyn Future<Output = Self::Output>>>;
+
+    fn make(&self, range: Range<usize>, is_rev: bool) -> Self::Future {
+        let indices: Vec<_> = if is_rev {
+            range.rev().collect()
+        } else {
+            range.collect()
+        };
+
+        let values = stream::iter(indices)
+            .map(self.f.clone())
+            .buffered(self.limit.max(1))
+            .collect::<Vec<_>>();
+        Box::pin(values.map(Vec::into_iter))
+    }
+}
+
 pub fn from_range_fn<F, Fut, I, V>(f: F) -> FromRangeFn<F>
 where
     F: Fn(Range<usize>, bool) -> Fut + Clone + 'static,
@@ -83,3 +168,665 @@ where
         Box::pin((self.f)(input, is_rev))
     }
 }
+
+pub fn try_from_fn<F, Fut, V, E>(f: F) -> TryFromFn<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<V, E>> + 'static,
+    V: 'static,
+    E: 'static,
+{
+    TryFromFn { f }
+}
+
+#[derive(Clone, Copy)]
+pub struct TryFromFn<F> {
+    f: F,
+}
+
+impl<F, Fut, V, E> TryFactory for TryFromFn<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<V, E>> + 'static,
+    V: 'static,
+    E: 'static,
+{
+    type Item = V;
+    type Error = E;
+    type Output = std::vec::IntoIter<V>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Output, E>>>>;
+
+    fn try_make(&self, range: Range<usize>, is_rev: bool) -> Self::Future {
+        let f = self.f.clone();
+        Box::pin(async move {
+            let mut values = Vec::new();
+
+            if is_rev {
+                for idx in range.rev() {
+                    values.push(f(idx).await?)
+                }
+            } else {
+                for idx in range {
+                    values.push(f(idx).await?)
+                }
+            }
+
+            Ok(values.into_iter())
+        })
+    }
+}
+
+pub fn try_from_range_fn<F, Fut, I, V, E>(f: F) -> TryFromRangeFn<F>
+where
+    F: Fn(Range<usize>, bool) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<I, E>> + 'static,
+    I: IntoIterator<Item = V>,
+    V: 'static,
+    E: 'static,
+{
+    TryFromRangeFn { f }
+}
+
+#[derive(Clone, Copy)]
+pub struct TryFromRangeFn<F> {
+    f: F,
+}
+
+impl<F, Fut, I, V, E> TryFactory for TryFromRangeFn<F>
+where
+    F: Fn(Range<usize>, bool) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<I, E>> + 'static,
+    I: IntoIterator<Item = V>,
+    V: 'static,
+    E: 'static,
+{
+    type Item = V;
+    type Error = E;
+    type Output = I;
+    type Future = Pin<Box<dyn Future<Output = Result<I, E>>>>;
+
+    fn try_make(&self, input: Range<usize>, is_rev: bool) -> Self::Future {
+        Box::pin((self.f)(input, is_rev))
+    }
+}
+
+/// Serve values from a new [`Cache`] of up to `capacity` values, only calling `factory` on misses.
+pub fn cached<F>(capacity: usize, factory: F) -> Cached<F>
+where
+    F: TryFactory,
+{
+    with_cache(Cache::new(capacity), factory)
+}
+
+/// Serve values from `cache`, only calling `factory` on misses.
+///
+/// Factories sharing a cache only load each value once, and values invalidated through
+/// any of them, or with [`Cache::invalidate`], are reloaded in every list using the cache.
+pub fn with_cache<F>(cache: Cache<F::Item>, factory: F) -> Cached<F>
+where
+    F: TryFactory,
+{
+    Cached {
+        factory: Rc::new(factory),
+        cache,
+        subscriber: Cell::new(None),
+    }
+}
+
+pub struct Cached<F: TryFactory> {
+    factory: Rc<F>,
+    cache: Cache<F::Item>,
+
+    /// Id of the listener subscribed through this factory, which isn't told about its own invalidations.
+    subscriber: Cell<Option<u64>>,
+}
+
+impl<F: TryFactory> Cached<F> {
+    /// Get the cache of values.
+    pub fn cache(&self) -> &Cache<F::Item> {
+        &self.cache
+    }
+}
+
+impl<F: TryFactory> Clone for Cached<F> {
+    fn clone(&self) -> Self {
+        Self {
+            factory: self.factory.clone(),
+            cache: self.cache.clone(),
+            subscriber: Cell::new(None),
+        }
+    }
+}
+
+/// Run of consecutive indices that were in the cache, being loaded elsewhere, or missing from it.
+enum Run<V> {
+    Hit(Vec<V>),
+    Wait(Range<usize>, Vec<Pending>),
+    Miss(Range<usize>),
+}
+
+impl<F> TryFactory for Cached<F>
+where
+    F: TryFactory + 'static,
+    F::Item: Clone + 'static,
+    F::Error: 'static,
+{
+    type Item = F::Item;
+    type Error = F::Error;
+    type Output = std::vec::IntoIter<F::Item>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Output, F::Error>>>>;
+
+    fn try_make(&self, range: Range<usize>, is_rev: bool) -> Self::Future {
+        let mut runs = Vec::new();
+        for idx in range {
+            let run = match (self.cache.get(idx), self.cache.pending(idx)) {
+                (Some(value), _) => Run::Hit(vec![value]),
+                (None, Some(pending)) => Run::Wait(idx..idx + 1, vec![pending]),
+                (None, None) => Run::Miss(idx..idx + 1),
+            };
+            match (runs.last_mut(), run) {
+                (Some(Run::Hit(values)), Run::Hit(value)) => values.extend(value),
+                (Some(Run::Wait(waits, pending)), Run::Wait(_, next)) => {
+                    waits.end = idx + 1;
+                    pending.extend(next);
+                }
+                (Some(Run::Miss(misses)), Run::Miss(_)) => misses.end = idx + 1,
+                (_, run) => runs.push(run),
+            }
+        }
+
+        // Mark the misses as loading right away, so other lists wait for them instead.
+        let loads: Vec<_> = runs
+            .iter()
+            .filter_map(|run| match run {
+                Run::Miss(misses) => Some((misses.clone(), self.cache.load(misses.clone()))),
+                _ => None,
+            })
+            .collect();
+
+        let factory = self.factory.clone();
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            let waits = future::join_all(runs.iter().filter_map(|run| match run {
+                Run::Wait(_, pending) => Some(future::join_all(pending.clone())),
+                _ => None,
+            }));
+            let loads = future::try_join_all(
+                loads
+                    .into_iter()
+                    .map(|(misses, loading)| load(&*factory, misses, loading, is_rev)),
+            );
+            let (loaded, _) = future::join(loads, waits).await;
+            let mut loaded = loaded?.into_iter();
+
+            let mut values = Vec::new();
+            for run in runs {
+                match run {
+                    Run::Hit(hits) => values.extend(hits),
+                    Run::Miss(_) => values.extend(loaded.next().unwrap_or_default()),
+                    Run::Wait(waits, _) => {
+                        let hits: Option<Vec<_>> =
+                            waits.clone().map(|idx| cache.get(idx)).collect();
+                        match hits {
+                            Some(hits) => values.extend(hits),
+                            // The other load failed or was invalidated, so load these here.
+                            None => {
+                                let loading = cache.load(waits.clone());
+                                values.extend(load(&*factory, waits, loading, is_rev).await?)
+                            }
+                        }
+                    }
+                }
+            }
+
+            if is_rev {
+                values.reverse();
+            }
+            Ok(values.into_iter())
+        })
+    }
+
+    fn invalidate(&self, range: Range<usize>) {
+        self.factory.invalidate(range.clone());
+        self.cache.invalidate_from(range, self.subscriber.get());
+    }
+
+    fn subscribe(&self, listener: Rc<dyn Fn(Range<usize>)>) -> Option<Subscription> {
+        let subscription = self.cache.subscribe(listener);
+        self.subscriber.set(Some(subscription.id()));
+        Some(subscription)
+    }
+}
+
+/// Make the values in `range` with `factory` in ascending order, inserting them into the cache.
+async fn load<F>(
+    factory: &F,
+    range: Range<usize>,
+    loading: Loading<F::Item>,
+    is_rev: bool,
+) -> Result<Vec<F::Item>, F::Error>
+where
+    F: TryFactory,
+    F::Item: Clone,
+{
+    let mut values: Vec<_> = factory
+        .try_make(range.clone(), is_rev)
+        .await?
+        .into_iter()
+        .collect();
+    if is_rev {
+        values.reverse();
+    }
+    for (idx, value) in range.zip(&values) {
+        loading.insert(idx, value.clone());
+    }
+    Ok(values)
+}
+
+/// Make values from fixed-size pages loaded with `fetch_page`, given the index of each page.
+///
+/// Up to `capacity` pages are kept, evicting the least recently used pages first,
+/// and every requested range is sliced out of the pages it overlaps.
+pub fn paged<F, Fut, I, V, E>(page_size: usize, capacity: usize, fetch_page: F) -> Paged<F, V>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<I, E>> + 'static,
+    I: IntoIterator<Item = V> + 'static,
+    V: Clone + 'static,
+    E: 'static,
+{
+    Paged {
+        fetch_page,
+        page_size: page_size.max(1),
+        pages: Cache::new(capacity),
+    }
+}
+
+pub struct Paged<F, V> {
+    fetch_page: F,
+    page_size: usize,
+    pages: Cache<Rc<[V]>>,
+}
+
+impl<F: Clone, V> Clone for Paged<F, V> {
+    fn clone(&self) -> Self {
+        Self {
+            fetch_page: self.fetch_page.clone(),
+            page_size: self.page_size,
+            pages: self.pages.clone(),
+        }
+    }
+}
+
+impl<F, Fut, I, V, E> TryFactory for Paged<F, V>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<I, E>> + 'static,
+    I: IntoIterator<Item = V> + 'static,
+    V: Clone + 'static,
+    E: 'static,
+{
+    type Item = V;
+    type Error = E;
+    type Output = std::vec::IntoIter<V>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Output, E>>>>;
+
+    fn try_make(&self, range: Range<usize>, is_rev: bool) -> Self::Future {
+        let page_size = self.page_size;
+        let first_page = range.start / page_size;
+        let pages = if range.is_empty() {
+            first_page..first_page
+        } else {
+            first_page..(range.end - 1) / page_size + 1
+        };
+
+        let fetches: Vec<_> = pages
+            .map(|page| load_page(self.fetch_page.clone(), self.pages.clone(), page))
+            .collect();
+        Box::pin(async move {
+            let mut values: Vec<_> = future::try_join_all(fetches)
+                .await?
+                .iter()
+                .flat_map(|page| page.iter().cloned())
+                .skip(range.start - first_page * page_size)
+                .take(range.len())
+                .collect();
+
+            if is_rev {
+                values.reverse();
+            }
+            Ok(values.into_iter())
+        })
+    }
+
+    fn invalidate(&self, range: Range<usize>) {
+        self.pages
+            .remove_range(range.start / self.page_size..range.end.div_ceil(self.page_size));
+    }
+}
+
+/// Get the values of `page` from `pages`, or wait for another load of it, or fetch it.
+async fn load_page<F, Fut, I, V, E>(
+    fetch_page: F,
+    pages: Cache<Rc<[V]>>,
+    page: usize,
+) -> Result<Rc<[V]>, E>
+where
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = Result<I, E>>,
+    I: IntoIterator<Item = V>,
+{
+    if let Some(pending) = pages.pending(page) {
+        pending.await.ok();
+    }
+    if let Some(values) = pages.get(page) {
+        return Ok(values);
+    }
+
+    let loading = pages.load(page..page + 1);
+    let values: Rc<[V]> = fetch_page(page).await?.into_iter().collect();
+    loading.insert(page, values.clone());
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::{
+        cell::{Cell, RefCell},
+        fmt,
+        rc::Rc,
+        task::Poll,
+    };
+
+    /// Factory of `idx * 10` that records every range it's asked for.
+    fn counting() -> (
+        impl TryFactory<Item = usize, Error = Infallible> + Clone,
+        Rc<RefCell<Vec<Range<usize>>>>,
+    ) {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let calls_clone = calls.clone();
+        let factory = try_from_range_fn(move |range: Range<usize>, is_rev| {
+            calls_clone.borrow_mut().push(range.clone());
+            let mut values: Vec<_> = range.map(|idx| idx * 10).collect();
+            if is_rev {
+                values.reverse();
+            }
+            async move { Ok(values) }
+        });
+        (factory, calls)
+    }
+
+    fn make<F: TryFactory>(factory: &F, range: Range<usize>, is_rev: bool) -> Vec<F::Item>
+    where
+        F::Error: fmt::Debug,
+    {
+        block_on(factory.try_make(range, is_rev))
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    /// Wait until polled `count` more times.
+    async fn yield_times(mut count: usize) {
+        future::poll_fn(|cx| {
+            if count == 0 {
+                return Poll::Ready(());
+            }
+            count -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Order that calls finished in, and the most calls running at once.
+    #[derive(Default)]
+    struct Calls {
+        finished: RefCell<Vec<usize>>,
+        running: Cell<usize>,
+        max_running: Cell<usize>,
+    }
+
+    /// Buffered factory of `idx * 10` where later indices finish first.
+    fn buffered(limit: usize) -> (impl TryFactory<Item = usize, Error = Infallible>, Rc<Calls>) {
+        let calls = Rc::new(Calls::default());
+        let calls_clone = calls.clone();
+        let factory = from_fn_buffered(limit, move |idx| {
+            let calls = calls_clone.clone();
+            async move {
+                calls.running.set(calls.running.get() + 1);
+                calls
+                    .max_running
+                    .set(calls.max_running.get().max(calls.running.get()));
+
+                yield_times(10 - idx).await;
+
+                calls.running.set(calls.running.get() - 1);
+                calls.finished.borrow_mut().push(idx);
+                idx * 10
+            }
+        });
+        (factory, calls)
+    }
+
+    #[test]
+    fn buffered_returns_values_in_index_order() {
+        let (factory, calls) = buffered(4);
+
+        assert_eq!(make(&factory, 0..4, false), [0, 10, 20, 30]);
+        assert_eq!(*calls.finished.borrow(), [3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn buffered_reverses_values() {
+        let (factory, calls) = buffered(4);
+
+        assert_eq!(make(&factory, 2..6, true), [50, 40, 30, 20]);
+        assert_eq!(*calls.finished.borrow(), [5, 4, 3, 2]);
+    }
+
+    #[test]
+    fn buffered_limits_calls_running_at_once() {
+        let (factory, calls) = buffered(2);
+        assert_eq!(make(&factory, 0..6, false), [0, 10, 20, 30, 40, 50]);
+        assert_eq!(calls.max_running.get(), 2);
+
+        // A limit of zero still makes one value at a time.
+        let (factory, calls) = buffered(0);
+        assert_eq!(make(&factory, 0..3, true), [20, 10, 0]);
+        assert_eq!(calls.max_running.get(), 1);
+        assert_eq!(*calls.finished.borrow(), [2, 1, 0]);
+    }
+
+    #[test]
+    fn cached_hits_skip_the_factory() {
+        let (factory, calls) = counting();
+        let cached = cached(100, factory);
+
+        assert_eq!(make(&cached, 0..5, false), [0, 10, 20, 30, 40]);
+        assert_eq!(make(&cached, 0..5, false), [0, 10, 20, 30, 40]);
+        assert_eq!(make(&cached, 3..7, false), [30, 40, 50, 60]);
+        assert_eq!(*calls.borrow(), [0..5, 5..7]);
+    }
+
+    #[test]
+    fn cached_only_fetches_runs_of_misses() {
+        let (factory, calls) = counting();
+        let cached = cached(100, factory);
+        make(&cached, 0..8, false);
+
+        cached.invalidate(1..3);
+        cached.invalidate(5..6);
+        calls.borrow_mut().clear();
+
+        assert_eq!(make(&cached, 0..8, false), [0, 10, 20, 30, 40, 50, 60, 70]);
+        assert_eq!(*calls.borrow(), [1..3, 5..6]);
+    }
+
+    #[test]
+    fn cached_preserves_reversed_order() {
+        let (factory, calls) = counting();
+        let cached = cached(100, factory);
+        make(&cached, 2..4, false);
+
+        assert_eq!(make(&cached, 0..6, true), [50, 40, 30, 20, 10, 0]);
+        assert_eq!(*calls.borrow(), [2..4, 0..2, 4..6]);
+
+        // Reversed values are cached at their own indices.
+        assert_eq!(make(&cached, 0..6, false), [0, 10, 20, 30, 40, 50]);
+        assert_eq!(calls.borrow().len(), 3);
+    }
+
+    #[test]
+    fn cached_evicts_least_recently_used() {
+        let (factory, calls) = counting();
+        let cached = cached(3, factory);
+        make(&cached, 0..3, false);
+        make(&cached, 3..4, false);
+
+        assert_eq!(make(&cached, 1..4, false), [10, 20, 30]);
+        assert_eq!(make(&cached, 0..1, false), [0]);
+        assert_eq!(*calls.borrow(), [0..3, 3..4, 0..1]);
+    }
+
+    #[test]
+    fn cached_with_zero_capacity_always_fetches() {
+        let (factory, calls) = counting();
+        let cached = cached(0, factory);
+
+        assert_eq!(make(&cached, 0..2, false), [0, 10]);
+        assert_eq!(make(&cached, 0..2, false), [0, 10]);
+        assert_eq!(*calls.borrow(), [0..2, 0..2]);
+    }
+
+    #[test]
+    fn cached_waits_for_loads_in_progress() {
+        let (factory, calls) = counting();
+        let cache = Cache::new(100);
+        let first = with_cache(cache.clone(), factory.clone());
+        let second = with_cache(cache, factory);
+
+        let (a, b) = block_on(future::join(
+            first.try_make(0..4, false),
+            second.try_make(2..6, false),
+        ));
+        assert_eq!(a.unwrap().collect::<Vec<_>>(), [0, 10, 20, 30]);
+        assert_eq!(b.unwrap().collect::<Vec<_>>(), [20, 30, 40, 50]);
+        assert_eq!(*calls.borrow(), [0..4, 4..6]);
+    }
+
+    #[test]
+    fn cached_notifies_other_subscribers() {
+        let (factory, calls) = counting();
+        let cache = Cache::new(100);
+        let first = with_cache(cache.clone(), factory.clone());
+        let second = with_cache(cache.clone(), factory);
+
+        let listen = |factory: &Cached<_>| {
+            let ranges = Rc::new(RefCell::new(Vec::new()));
+            let ranges_clone = ranges.clone();
+            let subscription = factory.subscribe(Rc::new(move |range| {
+                ranges_clone.borrow_mut().push(range);
+            }));
+            (ranges, subscription)
+        };
+        let (first_ranges, _first) = listen(&first);
+        let (second_ranges, _second) = listen(&second);
+        make(&first, 0..4, false);
+
+        // Only the other list is told about invalidations through its factory.
+        first.invalidate(1..3);
+        assert_eq!(*first_ranges.borrow(), []);
+        assert_eq!(*second_ranges.borrow(), [Range { start: 1, end: 3 }]);
+        assert_eq!(make(&second, 0..4, false), [0, 10, 20, 30]);
+        assert_eq!(*calls.borrow(), [0..4, 1..3]);
+
+        second.invalidate(0..1);
+        assert_eq!(*first_ranges.borrow(), [Range { start: 0, end: 1 }]);
+        assert_eq!(*second_ranges.borrow(), [Range { start: 1, end: 3 }]);
+
+        // Invalidating the cache itself reaches every list.
+        cache.invalidate(3..4);
+        assert_eq!(*first_ranges.borrow(), [0..1, 3..4]);
+        assert_eq!(*second_ranges.borrow(), [1..3, 3..4]);
+    }
+
+    /// Paged factory of 120 values in pages of 50, that records every page it fetches.
+    fn counting_pages(
+        capacity: usize,
+    ) -> (
+        impl TryFactory<Item = usize, Error = Infallible>,
+        Rc<RefCell<Vec<usize>>>,
+    ) {
+        let pages = Rc::new(RefCell::new(Vec::new()));
+        let pages_clone = pages.clone();
+        let factory = paged(50, capacity, move |page| {
+            pages_clone.borrow_mut().push(page);
+            let values: Vec<_> = (page * 50..(page * 50 + 50).min(120)).collect();
+            async move { Ok(values) }
+        });
+        (factory, pages)
+    }
+
+    #[test]
+    fn paged_slices_ranges_within_a_page() {
+        let (factory, pages) = counting_pages(10);
+
+        assert_eq!(make(&factory, 37..41, false), [37, 38, 39, 40]);
+        assert_eq!(make(&factory, 37..41, true), [40, 39, 38, 37]);
+        assert_eq!(*pages.borrow(), [0]);
+    }
+
+    #[test]
+    fn paged_slices_ranges_across_pages() {
+        let (factory, pages) = counting_pages(10);
+
+        assert_eq!(make(&factory, 48..53, false), [48, 49, 50, 51, 52]);
+        assert_eq!(make(&factory, 48..53, true), [52, 51, 50, 49, 48]);
+        assert_eq!(make(&factory, 0..2, false), [0, 1]);
+        assert_eq!(*pages.borrow(), [0, 1]);
+    }
+
+    #[test]
+    fn paged_returns_short_last_page() {
+        let (factory, pages) = counting_pages(10);
+
+        assert_eq!(make(&factory, 118..125, false), [118, 119]);
+        assert_eq!(make(&factory, 118..125, true), [119, 118]);
+        assert_eq!(*pages.borrow(), [2]);
+        assert!(make(&factory, 5..5, false).is_empty());
+        assert_eq!(pages.borrow().len(), 1);
+    }
+
+    #[test]
+    fn paged_invalidates_overlapping_pages() {
+        let (factory, pages) = counting_pages(10);
+        make(&factory, 0..120, false);
+        assert_eq!(*pages.borrow(), [0, 1, 2]);
+
+        factory.invalidate(48..51);
+        make(&factory, 0..120, false);
+        assert_eq!(*pages.borrow(), [0, 1, 2, 0, 1]);
+
+        factory.invalidate(100..usize::MAX);
+        make(&factory, 0..120, false);
+        assert_eq!(*pages.borrow(), [0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn paged_evicts_least_recently_used_pages() {
+        let (factory, pages) = counting_pages(2);
+        assert_eq!(make(&factory, 0..120, false).len(), 120);
+        assert_eq!(*pages.borrow(), [0, 1, 2]);
+
+        // The first page was evicted to make room for the last one.
+        assert_eq!(make(&factory, 0..1, false), [0]);
+        assert_eq!(make(&factory, 100..101, false), [100]);
+        assert_eq!(*pages.borrow(), [0, 1, 2, 0]);
+
+        assert_eq!(make(&factory, 50..51, false), [50]);
+        assert_eq!(*pages.borrow(), [0, 1, 2, 0, 1]);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    lazy::{ItemState, Lazy, Values},
+    use_grid::UseGrid,
+};
+use dioxus::prelude::*;
+
+#[derive(Props, Clone)]
+pub struct GridProps<F: 'static, G: 'static>
+where
+    F: Clone,
+    G: Clone,
+{
+    /// Number of cells in the grid, unless `make_value` knows its own length.
+    #[props(default)]
+    pub len: usize,
+
+    /// Number of columns in each row.
+    #[props(default = 1)]
+    pub columns: usize,
+
+    /// Width of the container.
+    pub width: f64,
+
+    /// Height of the container.
+    pub height: f64,
+
+    /// Width of each cell.
+    #[props(default = 100.)]
+    pub cell_width: f64,
+
+    /// Height of each cell.
+    pub cell_height: f64,
+
+    /// Minimum width of each cell, to derive `columns` and `cell_width`
+    /// from the container's measured width instead.
+    ///
+    /// The container fills the width of its parent in this mode.
+    pub min_cell_width: Option<f64>,
+
+    /// Function to create a new item.
+    pub make_item: F,
+
+    /// Function to create a new value.
+    pub make_value: G,
+
+    /// Function to create a placeholder for an item that's still loading.
+    pub make_placeholder: Option<Callback<usize, Element>>,
+
+    /// Event handler for scroll events.
+    pub onscroll: Option<EventHandler>,
+}
+
+impl<F: 'static, G: 'static> PartialEq for GridProps<F, G>
+where
+    F: Clone,
+    G: Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self.columns == other.columns
+            && self.width == other.width
+            && self.height == other.height
+            && self.cell_width == other.cell_width
+            && self.cell_height == other.cell_height
+            && self.min_cell_width == other.min_cell_width
+            && self.make_placeholder == other.make_placeholder
+            && self.onscroll == other.onscroll
+    }
+}
+
+/// Virtualized grid component.
+#[allow(non_snake_case)]
+pub fn Grid<T, F, G>(props: GridProps<F, G>) -> Element
+where
+    T: 'static,
+    F: Clone + 'static + Fn(&T) -> Element,
+    G: Clone + Lazy<Value = T> + Clone + 'static,
+{
+    let mut grid = UseGrid::builder()
+        .len(props.len)
+        .columns(props.columns)
+        .width(props.width)
+        .height(props.height)
+        .cell_width(props.cell_width)
+        .cell_height(props.cell_height)
+        .min_cell_width(props.min_cell_width)
+        .use_grid(props.make_value.clone());
+
+    let columns = *grid.columns.read();
+    let cell_width = *grid.column_range.item_size.read();
+    let cell_height = *grid.row_range.item_size.read();
+
+    let values_signal = grid.lazy.values();
+    let values_ref = values_signal.read();
+    let range_signal = grid.lazy.range();
+    let range_ref = range_signal.read();
+    let errors_signal = grid.lazy.errors();
+    let errors_ref = errors_signal.as_ref().map(|errors| errors.read());
+    let errors = errors_ref.as_deref().map(Vec::as_slice).unwrap_or_default();
+
+    let make_placeholder = props.make_placeholder;
+    let cells = grid.cells().into_iter().flatten().filter_map(|idx| {
+        let element = match ItemState::new(idx, &values_ref, &range_ref, errors) {
+            ItemState::Loaded(value) => (props.make_item)(value),
+            ItemState::Pending => make_placeholder?.call(idx),
+            ItemState::Failed(_) => return None,
+        };
+        let top = (idx / columns) as f64 * cell_height;
+        let left = (idx % columns) as f64 * cell_width;
+
+        Some(rsx!(
+            div {
+                key: "{idx}",
+                position: "absolute",
+                top: "{top}px",
+                left: "{left}px",
+                width: "{cell_width}px",
+                height: "{cell_height}px",
+                overflow: "hidden",
+                {element}
+            }
+        ))
+    });
+
+    let mounted = grid.mounted;
+    let width = match props.min_cell_width {
+        Some(_) => "100%".to_string(),
+        None => format!("{}px", grid.column_range.size.read()),
+    };
+    let height = *grid.row_range.size.read();
+    let inner_width = grid.column_range.inner_size();
+    let inner_height = grid.row_range.inner_size();
+    rsx!(
+        div {
+            width: "{width}",
+            height: "{height}px",
+            overflow: "scroll",
+            onmounted: move |event| mounted.onmounted(event),
+            onscroll: move |_| {
+                grid.scroll();
+                if let Some(handler) = &props.onscroll {
+                    handler.call(())
+                }
+            },
+            div {
+                position: "relative",
+                width: "{inner_width}px",
+                height: "{inner_height}px",
+                overflow: "hidden",
+                {cells}
+            }
+        }
+    )
+}
@@ -1,16 +1,223 @@
-use crate::{factory, use_lazy, use_lazy_async, UseLazy, UseLazyAsync};
+use crate::{
+    factory, use_lazy, use_lazy_async, use_lazy_signal, TryFactory, UseLazy, UseLazyAsync,
+    UseLazySignal,
+};
 use dioxus::prelude::*;
 use futures::Future;
-use std::{collections::VecDeque, ops::Range};
+use std::{collections::VecDeque, fmt, ops::Range, rc::Rc};
 
 pub trait Values: Clone {
-    type Value;
+    type Value: 'static;
+    type Error: 'static;
 
     fn values(&self) -> Signal<VecDeque<Self::Value>>;
 
+    /// Get the range of indices currently held in [`Values::values`].
+    fn range(&self) -> Signal<Range<usize>>;
+
     fn set(&mut self, range: Range<usize>);
 
     fn refresh(&mut self);
+
+    /// Reload the value at `idx`.
+    fn invalidate(&mut self, idx: usize) {
+        self.invalidate_range(idx..idx + 1)
+    }
+
+    /// Reload the values in `range`, keeping the current values until the new ones are ready.
+    ///
+    /// Indices outside of [`Values::range`] aren't loaded, so they're ignored.
+    fn invalidate_range(&mut self, _range: Range<usize>) {
+        self.refresh()
+    }
+
+    /// Shift every index by `by`, after that many items were inserted before the first one.
+    fn shift(&mut self, by: usize);
+
+    /// Get the ranges that failed to load, if these values can fail.
+    fn errors(&self) -> Option<Signal<Vec<RangeError<Self::Error>>>> {
+        None
+    }
+
+    /// Update the value at `idx` in place with `f`.
+    ///
+    /// Indices outside of [`Values::range`] aren't loaded, so they're ignored.
+    fn update(&mut self, idx: usize, f: impl FnOnce(&mut Self::Value)) {
+        let range = self.range().peek().clone();
+        if range.contains(&idx) {
+            if let Some(value) = self.values().write().get_mut(idx - range.start) {
+                f(value);
+            }
+        }
+    }
+
+    /// Insert `value` at `idx`, moving every later index up by one.
+    ///
+    /// The value is kept until it's reloaded, like with [`Values::invalidate`].
+    fn insert(&mut self, idx: usize, value: Self::Value) {
+        insert_value(self.values(), self.range(), idx, value)
+    }
+
+    /// Remove the value at `idx`, moving every later index down by one.
+    ///
+    /// Returns the removed value if it was loaded.
+    fn remove(&mut self, idx: usize) -> Option<Self::Value> {
+        remove_value(self.values(), self.range(), idx)
+    }
+
+    /// Retry loading every failed range.
+    fn retry(&mut self) {}
+
+    /// Get the ranges of indices currently being fetched, if these values load asynchronously.
+    fn pending(&self) -> Option<Signal<Vec<Range<usize>>>> {
+        None
+    }
+
+    /// Suspend rendering until the initial values are ready, when rendering on the server.
+    fn suspend(&self) -> Result<(), RenderError> {
+        Ok(())
+    }
+}
+
+/// Values that can be rendered on the server and sent to the client to hydrate.
+#[cfg(feature = "fullstack")]
+pub trait Hydrate: serde::Serialize + serde::de::DeserializeOwned + Clone + 'static {}
+
+#[cfg(feature = "fullstack")]
+impl<T> Hydrate for T where T: serde::Serialize + serde::de::DeserializeOwned + Clone + 'static {}
+
+/// Insert `value` at `idx` into `values` holding the `range` of loaded indices.
+pub(crate) fn insert_value<V>(
+    mut values: Signal<VecDeque<V>>,
+    mut range: Signal<Range<usize>>,
+    idx: usize,
+    value: V,
+) {
+    let current = range.peek().clone();
+    if idx < current.start {
+        range.set(current.start + 1..current.end + 1);
+    } else if idx <= current.end {
+        // Values past the end of the loaded ones can't be placed, so they're left to load.
+        let mut values_ref = values.write();
+        if idx - current.start <= values_ref.len() {
+            values_ref.insert(idx - current.start, value);
+            range.set(current.start..current.end + 1);
+        }
+    }
+}
+
+/// Remove the value at `idx` from `values` holding the `range` of loaded indices.
+pub(crate) fn remove_value<V>(
+    mut values: Signal<VecDeque<V>>,
+    mut range: Signal<Range<usize>>,
+    idx: usize,
+) -> Option<V> {
+    let current = range.peek().clone();
+    if idx < current.start {
+        range.set(current.start - 1..current.end - 1);
+        None
+    } else if idx < current.end {
+        range.set(current.start..current.end - 1);
+        values.write().remove(idx - current.start)
+    } else {
+        None
+    }
+}
+
+/// Move `range` to account for an item inserted at `idx`.
+pub(crate) fn insert_index(range: &Range<usize>, idx: usize) -> Range<usize> {
+    if idx <= range.start {
+        range.start + 1..range.end + 1
+    } else if idx < range.end {
+        range.start..range.end + 1
+    } else {
+        range.clone()
+    }
+}
+
+/// Move `range` to account for the item at `idx` being removed.
+pub(crate) fn remove_index(range: &Range<usize>, idx: usize) -> Range<usize> {
+    if idx < range.start {
+        range.start - 1..range.end - 1
+    } else if idx < range.end {
+        range.start..range.end - 1
+    } else {
+        range.clone()
+    }
+}
+
+/// Loading state of an item.
+pub enum ItemState<'a, V, E> {
+    /// The item's value is loaded.
+    Loaded(&'a V),
+
+    /// The item's value is still being fetched.
+    Pending,
+
+    /// The item is in a range that failed to load.
+    Failed(&'a RangeError<E>),
+}
+
+impl<'a, V, E> ItemState<'a, V, E> {
+    /// Get the state of the item at `idx`,
+    /// from `values` holding the `range` of loaded indices and the current `errors`.
+    pub fn new(
+        idx: usize,
+        values: &'a VecDeque<V>,
+        range: &Range<usize>,
+        errors: &'a [RangeError<E>],
+    ) -> Self {
+        if range.contains(&idx) {
+            if let Some(value) = values.get(idx - range.start) {
+                return Self::Loaded(value);
+            }
+        }
+
+        errors
+            .iter()
+            .find(|error| error.range.contains(&idx))
+            .map(Self::Failed)
+            .unwrap_or(Self::Pending)
+    }
+}
+
+/// Range of indices that failed to load.
+pub struct RangeError<E> {
+    pub range: Range<usize>,
+    pub error: Rc<E>,
+}
+
+impl<E> RangeError<E> {
+    pub fn new(range: Range<usize>, error: E) -> Self {
+        Self {
+            range,
+            error: Rc::new(error),
+        }
+    }
+}
+
+impl<E> Clone for RangeError<E> {
+    fn clone(&self) -> Self {
+        Self {
+            range: self.range.clone(),
+            error: self.error.clone(),
+        }
+    }
+}
+
+impl<E> PartialEq for RangeError<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.range == other.range && Rc::ptr_eq(&self.error, &other.error)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for RangeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RangeError")
+            .field("range", &self.range)
+            .field("error", &self.error)
+            .finish()
+    }
 }
 
 pub trait Lazy {
@@ -18,6 +225,25 @@ pub trait Lazy {
     type Values: Values<Value = Self::Value>;
 
     fn values(self) -> Self::Values;
+
+    /// Get the number of values, if they know their own length.
+    ///
+    /// This is read while rendering, so reading a signal here re-renders the list when it changes.
+    fn known_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Use the values, loading the `initial` range during the first render
+    /// so it can be rendered on the server.
+    fn values_with_initial(self, initial: Range<usize>) -> Self::Values
+    where
+        Self: Sized,
+    {
+        let values = self.values();
+        let mut values_clone = values.clone();
+        use_hook(move || values_clone.set(initial));
+        values
+    }
 }
 
 pub fn from_fn<F, V>(f: F) -> FromFn<F>
@@ -105,6 +331,36 @@ where
     }
 }
 
+/// Load each value with `f`, running up to `limit` calls at once.
+pub fn from_async_fn_buffered<F, Fut, V>(limit: usize, f: F) -> FromAsyncFnBuffered<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = V> + 'static,
+    V: 'static,
+{
+    FromAsyncFnBuffered { f, limit }
+}
+
+#[derive(Clone, Copy)]
+pub struct FromAsyncFnBuffered<F> {
+    f: F,
+    limit: usize,
+}
+
+impl<F, Fut, V> Lazy for FromAsyncFnBuffered<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = V> + 'static,
+    V: 'static,
+{
+    type Value = V;
+    type Values = UseLazyAsync<V>;
+
+    fn values(self) -> Self::Values {
+        use_lazy_async(factory::from_fn_buffered(self.limit, self.f))
+    }
+}
+
 pub fn from_async_range_fn<F, Fut, I, V>(f: F) -> FromAsyncRangeFn<F>
 where
     F: Fn(Range<usize>, bool) -> Fut + Clone + 'static,
@@ -134,3 +390,163 @@ where
         use_lazy_async(factory::from_range_fn(self.f))
     }
 }
+
+pub fn try_from_async_fn<F, Fut, V, E>(f: F) -> TryFromAsyncFn<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<V, E>> + 'static,
+    V: 'static,
+    E: 'static,
+{
+    TryFromAsyncFn { f }
+}
+
+#[derive(Clone, Copy)]
+pub struct TryFromAsyncFn<F> {
+    f: F,
+}
+
+impl<F, Fut, V, E> Lazy for TryFromAsyncFn<F>
+where
+    F: Fn(usize) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<V, E>> + 'static,
+    V: 'static,
+    E: 'static,
+{
+    type Value = V;
+    type Values = UseLazyAsync<V, E>;
+
+    fn values(self) -> Self::Values {
+        use_lazy_async(factory::try_from_fn(self.f))
+    }
+}
+
+pub fn try_from_async_range_fn<F, Fut, I, V, E>(f: F) -> TryFromAsyncRangeFn<F>
+where
+    F: Fn(Range<usize>, bool) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<I, E>> + 'static,
+    I: IntoIterator<Item = V>,
+    V: 'static,
+    E: 'static,
+{
+    TryFromAsyncRangeFn { f }
+}
+
+#[derive(Clone, Copy)]
+pub struct TryFromAsyncRangeFn<F> {
+    f: F,
+}
+
+impl<F, Fut, I, V, E> Lazy for TryFromAsyncRangeFn<F>
+where
+    F: Fn(Range<usize>, bool) -> Fut + Clone + 'static,
+    Fut: Future<Output = Result<I, E>> + 'static,
+    I: IntoIterator<Item = V>,
+    V: 'static,
+    E: 'static,
+{
+    type Value = V;
+    type Values = UseLazyAsync<V, E>;
+
+    fn values(self) -> Self::Values {
+        use_lazy_async(factory::try_from_range_fn(self.f))
+    }
+}
+
+/// Use the values made by `factory`, such as a [`factory::cached`] factory.
+pub fn from_factory<F>(factory: F) -> FromFactory<F>
+where
+    F: TryFactory + 'static,
+{
+    FromFactory { factory }
+}
+
+#[derive(Clone, Copy)]
+pub struct FromFactory<F> {
+    factory: F,
+}
+
+impl<F> Lazy for FromFactory<F>
+where
+    F: TryFactory + 'static,
+{
+    type Value = F::Item;
+    type Values = UseLazyAsync<F::Item, F::Error>;
+
+    fn values(self) -> Self::Values {
+        use_lazy_async(self.factory)
+    }
+}
+
+/// Use the values made by `factory`, loading the initial range on the server
+/// and hydrating the client with the same values.
+#[cfg(feature = "fullstack")]
+pub fn from_factory_hydrated<F>(factory: F) -> FromFactoryHydrated<F>
+where
+    F: TryFactory + 'static,
+    F::Item: Hydrate,
+{
+    FromFactoryHydrated { factory }
+}
+
+#[cfg(feature = "fullstack")]
+#[derive(Clone, Copy)]
+pub struct FromFactoryHydrated<F> {
+    factory: F,
+}
+
+#[cfg(feature = "fullstack")]
+impl<F> Lazy for FromFactoryHydrated<F>
+where
+    F: TryFactory + 'static,
+    F::Item: Hydrate,
+{
+    type Value = F::Item;
+    type Values = UseLazyAsync<F::Item, F::Error>;
+
+    fn values(self) -> Self::Values {
+        self.values_with_initial(0..0)
+    }
+
+    fn values_with_initial(self, initial: Range<usize>) -> Self::Values {
+        crate::use_lazy_async_hydrated(self.factory, initial)
+    }
+}
+
+/// Use the values of a signal, keeping the list's length in sync with it.
+///
+/// Only the values in view are cloned out of `data`, and they're taken again when it changes.
+pub fn from_signal<T>(data: Signal<Vec<T>>) -> FromSignal<T>
+where
+    T: Clone + 'static,
+{
+    FromSignal { data }
+}
+
+pub struct FromSignal<T: 'static> {
+    data: Signal<Vec<T>>,
+}
+
+impl<T> Clone for FromSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FromSignal<T> {}
+
+impl<T> Lazy for FromSignal<T>
+where
+    T: Clone + 'static,
+{
+    type Value = T;
+    type Values = UseLazySignal<T>;
+
+    fn values(self) -> Self::Values {
+        use_lazy_signal(self.data)
+    }
+
+    fn known_len(&self) -> Option<usize> {
+        Some(self.data.read().len())
+    }
+}
@@ -1,25 +1,88 @@
+mod cache;
+pub use cache::{Cache, Subscription};
+
 pub mod factory;
-pub use factory::Factory;
+pub use factory::{Factory, TryFactory};
+
+mod grid;
+pub use grid::{Grid, GridProps};
 
 pub mod lazy;
 
 mod list;
-pub use list::{List, ListProps};
+pub use list::{KeyFn, List, ListError, ListItem, ListProps};
 
 mod use_lazy;
 pub use use_lazy::{use_lazy, UseLazy};
 
+mod use_lazy_signal;
+pub use use_lazy_signal::{use_lazy_signal, UseLazySignal};
+
 mod use_lazy_async;
+#[cfg(feature = "fullstack")]
+pub use use_lazy_async::use_lazy_async_hydrated;
 pub use use_lazy_async::{use_lazy_async, UseLazyAsync};
 
+mod platform;
+
+mod sizes;
+pub use sizes::Sizes;
+
+pub mod use_grid;
+pub use use_grid::UseGrid;
+
 pub mod use_list;
 pub use use_list::UseList;
 
 pub mod use_scroll_range;
-pub use use_scroll_range::UseScrollRange;
+pub use use_scroll_range::{Overscan, UseScrollRange};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Axis along which a list is laid out and scrolled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Direction {
+    /// Items are stacked vertically and scrolled along the y axis.
+    #[default]
     Row,
+
+    /// Items are placed side by side and scrolled along the x axis.
     Column,
 }
+
+/// Size of a list's viewport along its direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Size {
+    /// Fixed size in pixels.
+    Pixels(f64),
+
+    /// Fill the parent, measuring the mounted element as it resizes.
+    Auto,
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self::Pixels(400.)
+    }
+}
+
+impl From<f64> for Size {
+    fn from(size: f64) -> Self {
+        Self::Pixels(size)
+    }
+}
+
+/// Alignment of an item scrolled into view.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    /// Align the item with the start of the viewport.
+    Start,
+
+    /// Center the item in the viewport.
+    Center,
+
+    /// Align the item with the end of the viewport.
+    End,
+
+    /// Scroll as little as possible to bring the item into view.
+    #[default]
+    Auto,
+}
@@ -1,44 +1,220 @@
 use crate::{
-    lazy::{Lazy, Values},
+    lazy::{ItemState, Lazy, Values},
+    platform,
     use_list::UseList,
+    Direction, Overscan, Size, Sizes,
 };
 use dioxus::prelude::*;
+use dioxus_use_mounted::use_mounted;
+use std::{fmt, ops::Range, rc::Rc};
+
+/// Item passed to [`ListProps::make_item`].
+pub struct ListItem<'a, T> {
+    /// Absolute index of the item.
+    pub idx: usize,
+
+    /// Value of the item.
+    pub value: &'a T,
+
+    /// Offset of the item along the list's direction, from measured sizes when measuring items.
+    pub offset: f64,
+
+    /// Whether the item is [`ListProps::focused`].
+    pub is_focused: bool,
+
+    /// Whether the item is in [`ListProps::selected`].
+    pub is_selected: bool,
+}
+
+/// Range that failed to load, passed to [`ListProps::make_error`].
+pub struct ListError<E> {
+    /// Range of item indices that failed to load.
+    pub range: Range<usize>,
+
+    /// Error returned by the factory.
+    pub error: Rc<E>,
+
+    /// Callback to retry loading every failed range.
+    pub retry: Callback,
+}
+
+impl<E> Clone for ListError<E> {
+    fn clone(&self) -> Self {
+        Self {
+            range: self.range.clone(),
+            error: self.error.clone(),
+            retry: self.retry,
+        }
+    }
+}
+
+/// Function to create the key of an item from its value and index, passed to [`ListProps::key_fn`].
+pub struct KeyFn<T> {
+    f: Rc<KeyFnInner<T>>,
+}
+
+type KeyFnInner<T> = dyn Fn(&T, usize) -> String;
+
+impl<T> KeyFn<T> {
+    /// Get the key of the item at `idx`.
+    pub fn key(&self, value: &T, idx: usize) -> String {
+        (self.f)(value, idx)
+    }
+}
+
+impl<T, F, K> From<F> for KeyFn<T>
+where
+    F: Fn(&T, usize) -> K + 'static,
+    K: fmt::Display,
+{
+    fn from(f: F) -> Self {
+        Self {
+            f: Rc::new(move |value, idx| f(value, idx).to_string()),
+        }
+    }
+}
+
+impl<T> Clone for KeyFn<T> {
+    fn clone(&self) -> Self {
+        Self { f: self.f.clone() }
+    }
+}
+
+impl<T> Default for KeyFn<T> {
+    fn default() -> Self {
+        Self {
+            f: Rc::new(|_, idx| idx.to_string()),
+        }
+    }
+}
+
+type ErrorOf<G> = <<G as Lazy>::Values as Values>::Error;
+
+type ValueOf<G> = <G as Lazy>::Value;
 
 #[derive(Props, Clone)]
 pub struct ListProps<F: 'static, G: 'static>
 where
     F: Clone,
-    G: Clone,
+    G: Clone + Lazy,
 {
-    /// Length of the list.
+    /// Length of the list, unless `make_value` knows its own length (like [`lazy::from_signal`]).
+    ///
+    /// [`lazy::from_signal`]: crate::lazy::from_signal
+    #[props(default)]
     pub len: usize,
 
-    /// Size of the container.
-    pub size: f64,
+    /// Direction of the list.
+    #[props(default)]
+    pub direction: Direction,
+
+    /// Size of the container, or [`Size::Auto`] to fill its parent.
+    #[props(into, default)]
+    pub size: Size,
 
     /// Size of each item.
     pub item_size: f64,
 
+    /// Overscan before and after the viewport.
+    #[props(default)]
+    pub overscan: Overscan,
+
+    /// Overscan ahead of the viewport in the current scroll direction,
+    /// or `None` to use `overscan` for both edges.
+    pub overscan_ahead: Option<Overscan>,
+
+    /// Measure each item as it renders, using `item_size` as an estimate until then.
+    #[props(default)]
+    pub measure_items: bool,
+
     /// Function to create a new item.
     pub make_item: F,
 
     /// Function to create a new value.
     pub make_value: G,
 
+    /// Index of the focused item.
+    pub focused: Option<usize>,
+
+    /// Indices of the selected items.
+    #[props(default)]
+    pub selected: Vec<usize>,
+
+    /// Function to create the key of each item from its value and index.
+    ///
+    /// Items are keyed by their index by default.
+    /// Keying by the data keeps each item's elements and state with it as items move.
+    #[props(default, into)]
+    pub key_fn: KeyFn<ValueOf<G>>,
+
+    /// Function to create a placeholder for an item that's still loading.
+    pub make_placeholder: Option<Callback<usize, Element>>,
+
+    /// Keep showing items that are being reloaded until their new values are ready,
+    /// instead of showing placeholders in their place.
+    #[props(default = true)]
+    pub stale_while_revalidate: bool,
+
+    /// Function to create an element in place of a range that failed to load.
+    pub make_error: Option<Callback<ListError<ErrorOf<G>>, Element>>,
+
+    /// Number of items that have been prepended to the list.
+    ///
+    /// Increase this along with `len` when prepending items to keep the same items in view.
+    #[props(default)]
+    pub origin: usize,
+
+    /// Start scrolled to the end, and stay there as items are appended while at the end.
+    #[props(default)]
+    pub stick_to_end: bool,
+
+    /// Scroll offset to start at, which sets the range rendered on the server.
+    #[props(default)]
+    pub initial_scroll: i32,
+
+    /// Scroll with the document instead of a container of `size`.
+    ///
+    /// The list takes up its full size in the page, and `onscroll` isn't called in this mode.
+    /// This requires the `web` feature, and is ignored otherwise.
+    #[props(default)]
+    pub window_scroll: bool,
+
     /// Event handler for scroll events.
     pub onscroll: Option<EventHandler>,
+
+    /// Event handler for when the viewport gets within `end_threshold` of the end of the list.
+    pub on_end_reached: Option<EventHandler>,
+
+    /// Distance in pixels from the end of the list that triggers `on_end_reached`.
+    #[props(default)]
+    pub end_threshold: f64,
 }
 
 impl<F: 'static, G: 'static> PartialEq for ListProps<F, G>
 where
     F: Clone,
-    G: Clone,
+    G: Clone + Lazy,
 {
     fn eq(&self, other: &Self) -> bool {
         self.len == other.len
+            && self.direction == other.direction
             && self.size == other.size
             && self.item_size == other.item_size
+            && self.measure_items == other.measure_items
+            && self.overscan == other.overscan
+            && self.overscan_ahead == other.overscan_ahead
+            && self.focused == other.focused
+            && self.selected == other.selected
+            && self.make_placeholder == other.make_placeholder
+            && self.stale_while_revalidate == other.stale_while_revalidate
+            && self.make_error == other.make_error
+            && self.origin == other.origin
+            && self.stick_to_end == other.stick_to_end
+            && self.window_scroll == other.window_scroll
+            && self.initial_scroll == other.initial_scroll
             && self.onscroll == other.onscroll
+            && self.on_end_reached == other.on_end_reached
+            && self.end_threshold == other.end_threshold
     }
 }
 
@@ -47,52 +223,270 @@ where
 pub fn List<T, F, G>(props: ListProps<F, G>) -> Element
 where
     T: 'static,
-    F: Clone + 'static + Fn(&T) -> Element,
+    F: Clone + 'static + Fn(ListItem<T>) -> Element,
     G: Clone + Lazy<Value = T> + Clone + 'static,
 {
-    let mut list = UseList::builder()
+    let mut builder = UseList::builder();
+    if let Some(handler) = props.on_end_reached {
+        builder.on_end_reached(props.end_threshold, move || handler.call(()));
+    }
+    let mut list = builder
+        .direction(props.direction)
         .len(props.len)
         .size(props.size)
         .item_size(props.item_size)
+        .measure_items(props.measure_items)
+        .overscan(props.overscan)
+        .overscan_ahead(props.overscan_ahead)
+        .origin(props.origin)
+        .stick_to_end(props.stick_to_end)
+        .window_scroll(props.window_scroll)
+        .initial_scroll(props.initial_scroll)
         .use_list(props.make_value.clone());
 
+    // Every hook is created before suspending, so they're in the same order on the next render.
+    let mut lazy = list.lazy.clone();
+    let retry = use_callback(move |_| lazy.retry());
+    list.lazy.suspend()?;
+
+    let scroll_range = list.scroll_range;
     let values_signal = list.lazy.values();
     let values_ref = values_signal.read();
-    let rows = values_ref.iter().enumerate().map(move |(idx, value)| {
-        let top = (list.scroll_range.start() + idx) as f64 * *list.scroll_range.item_size.read();
-        rsx!(
+    let range_signal = list.lazy.range();
+    let range_ref = range_signal.read();
+    let errors_signal = list.lazy.errors();
+    let errors_ref = errors_signal.as_ref().map(|errors| errors.read());
+    let errors = errors_ref.as_deref().map(Vec::as_slice).unwrap_or_default();
+    let pending_signal = list.lazy.pending();
+    let pending_ref = pending_signal.as_ref().map(|pending| pending.read());
+    let pending = pending_ref
+        .as_deref()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let make_placeholder = props.make_placeholder;
+    let key_fn = &props.key_fn;
+    let rows = scroll_range.range().filter_map(|idx| {
+        let offset = scroll_range.offset(idx);
+        let mut state = ItemState::new(idx, &values_ref, &range_ref, errors);
+        if !props.stale_while_revalidate
+            && matches!(state, ItemState::Loaded(_))
+            && pending.iter().any(|range| range.contains(&idx))
+        {
+            state = ItemState::Pending;
+        }
+
+        let (key, element) = match state {
+            ItemState::Loaded(value) => {
+                let element = (props.make_item)(ListItem {
+                    idx,
+                    value,
+                    offset,
+                    is_focused: props.focused == Some(idx),
+                    is_selected: props.selected.contains(&idx),
+                });
+                (key_fn.key(value, idx), element)
+            }
+            ItemState::Pending => (format!("placeholder-{idx}"), make_placeholder?.call(idx)),
+            ItemState::Failed(_) => return None,
+        };
+
+        if let Some(sizes) = scroll_range.sizes {
+            return Some(rsx!(
+                MeasuredItem {
+                    key: "{key}",
+                    idx,
+                    direction: scroll_range.direction,
+                    offset,
+                    sizes,
+                    {element}
+                }
+            ));
+        }
+
+        let item_size = *scroll_range.item_size.read();
+        Some(slot(
+            scroll_range.direction,
+            key,
+            offset,
+            item_size,
+            element,
+        ))
+    });
+
+    let make_error = props.make_error;
+    let error_slots = errors.iter().filter_map(|error| {
+        let make_error = make_error?;
+        let start = scroll_range.offset(error.range.start);
+        let size = scroll_range.offset(error.range.end) - start;
+        let key = format!("error-{}", error.range.start);
+        let element = make_error.call(ListError {
+            range: error.range.clone(),
+            error: error.error.clone(),
+            retry,
+        });
+
+        Some(slot(scroll_range.direction, key, start, size, element))
+    });
+
+    let direction = list.scroll_range.direction;
+    let window_scroll = list.window_scroll;
+    let mounted = list.mounted;
+    let size = match props.size {
+        Size::Pixels(_) => format!("{}px", list.scroll_range.size.read()),
+        Size::Auto => "100%".to_string(),
+    };
+    let inner_size = list.scroll_range.inner_size();
+    let onscroll = move |_| {
+        list.scroll();
+        if let Some(handler) = &props.onscroll {
+            handler.call(())
+        }
+    };
+
+    if window_scroll {
+        return match direction {
+            Direction::Row => rsx!(
+                div {
+                    position: "relative",
+                    height: "{inner_size}px",
+                    overflow: "hidden",
+                    onmounted: move |event| mounted.onmounted(event),
+                    {rows}
+                    {error_slots}
+                }
+            ),
+            Direction::Column => rsx!(
+                div {
+                    position: "relative",
+                    width: "{inner_size}px",
+                    height: "100%",
+                    overflow: "hidden",
+                    onmounted: move |event| mounted.onmounted(event),
+                    {rows}
+                    {error_slots}
+                }
+            ),
+        };
+    }
+
+    match direction {
+        Direction::Row => rsx!(
             div {
-                key: "{top}",
+                height: "{size}",
+                overflow: "scroll",
+                onmounted: move |event| mounted.onmounted(event),
+                onscroll,
+                div {
+                    position: "relative",
+                    height: "{inner_size}px",
+                    overflow: "hidden",
+                    {rows}
+                    {error_slots}
+                }
+            }
+        ),
+        Direction::Column => rsx!(
+            div {
+                width: "{size}",
+                overflow: "scroll",
+                onmounted: move |event| mounted.onmounted(event),
+                onscroll,
+                div {
+                    position: "relative",
+                    width: "{inner_size}px",
+                    height: "100%",
+                    overflow: "hidden",
+                    {rows}
+                    {error_slots}
+                }
+            }
+        ),
+    }
+}
+
+/// Absolutely positioned slot at `offset` along the list's axis.
+fn slot(direction: Direction, key: String, offset: f64, size: f64, children: Element) -> Element {
+    match direction {
+        Direction::Row => rsx!(
+            div {
+                key: "{key}",
                 position: "absolute",
-                top: "{top}px",
+                top: "{offset}px",
                 left: 0,
                 width: "100%",
-                height: "{list.scroll_range.item_size}px",
+                height: "{size}px",
                 overflow: "hidden",
-                {(props.make_item)(value)}
+                {children}
             }
-        )
-    });
-
-    let size = *list.scroll_range.size.read();
-    let inner_size = list.scroll_range.item_size * props.len as f64;
-    rsx!(
-        div {
-            height: "{size}px",
-            overflow: "scroll",
-            onmounted: move |event| list.mounted.onmounted(event),
-            onscroll: move |_| {
-                list.scroll();
-                if let Some(handler) = &props.onscroll {
-                    handler.call(())
-                }
-            },
+        ),
+        Direction::Column => rsx!(
             div {
-                position: "relative",
-                height: "{inner_size}px",
+                key: "{key}",
+                position: "absolute",
+                top: 0,
+                left: "{offset}px",
+                width: "{size}px",
+                height: "100%",
                 overflow: "hidden",
-                {rows}
+                {children}
+            }
+        ),
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct MeasuredItemProps {
+    idx: usize,
+    direction: Direction,
+    offset: f64,
+    sizes: Signal<Sizes>,
+    children: Element,
+}
+
+/// List item that reports its measured size.
+///
+/// An item's index can change as items move, so its size is reported again for its new index.
+#[allow(non_snake_case)]
+fn MeasuredItem(props: MeasuredItemProps) -> Element {
+    let mounted = use_mounted();
+    let element_size = platform::use_size(mounted);
+
+    let direction = props.direction;
+    let mut sizes = props.sizes;
+    use_effect(use_reactive(&props.idx, move |idx| {
+        if let Some((width, height)) = element_size() {
+            let size = match direction {
+                Direction::Row => height,
+                Direction::Column => width,
+            };
+            if sizes.peek().get(idx) != size {
+                sizes.write().set(idx, size);
             }
         }
-    )
+    }));
+
+    let offset = props.offset;
+    match direction {
+        Direction::Row => rsx!(
+            div {
+                position: "absolute",
+                top: "{offset}px",
+                left: 0,
+                width: "100%",
+                onmounted: move |event| mounted.onmounted(event),
+                {props.children}
+            }
+        ),
+        Direction::Column => rsx!(
+            div {
+                position: "absolute",
+                top: 0,
+                left: "{offset}px",
+                height: "100%",
+                onmounted: move |event| mounted.onmounted(event),
+                {props.children}
+            }
+        ),
+    }
 }
@@ -0,0 +1,235 @@
+//! Access to mounted elements, through web-sys with the `web` feature
+//! or through the renderer-agnostic [`MountedData`] otherwise.
+
+use crate::Direction;
+use dioxus::prelude::*;
+use dioxus_use_mounted::UseMounted;
+use std::rc::Rc;
+
+#[cfg(feature = "web")]
+pub(crate) use web::*;
+
+#[cfg(not(feature = "web"))]
+pub(crate) use native::*;
+
+#[cfg(feature = "web")]
+mod web {
+    use super::*;
+    use dioxus::web::WebEventExt;
+    use dioxus_resize_observer::use_resize;
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::ScrollToOptions;
+
+    /// Use the `(width, height)` of the mounted element, updated as it resizes.
+    pub(crate) fn use_size(mounted: UseMounted) -> Memo<Option<(f64, f64)>> {
+        let resize = use_resize(mounted);
+        use_memo(move || {
+            resize
+                .read()
+                .as_ref()
+                .map(|rect| (rect.width(), rect.height()))
+        })
+    }
+
+    /// Read the `(left, top)` scroll offset of `mounted` and pass it to `f`.
+    pub(crate) fn read_scroll(mounted: Rc<MountedData>, f: impl FnOnce(i32, i32) + 'static) {
+        let elem = mounted.try_as_web_event().unwrap();
+        f(elem.scroll_left(), elem.scroll_top())
+    }
+
+    /// Scroll `mounted` to `offset` along `direction`.
+    pub(crate) fn scroll_to(
+        mounted: &MountedData,
+        direction: Direction,
+        offset: f64,
+        behavior: ScrollBehavior,
+    ) {
+        let elem = mounted.try_as_web_event().unwrap();
+        elem.scroll_to_with_scroll_to_options(&scroll_options(direction, offset, behavior));
+    }
+
+    /// Move `mounted` to `scroll` along `direction` if it isn't already there.
+    pub(crate) fn sync_scroll(mounted: &MountedData, direction: Direction, scroll: i32) {
+        let elem = mounted.try_as_web_event().unwrap();
+        match direction {
+            Direction::Row if elem.scroll_top() != scroll => elem.set_scroll_top(scroll),
+            Direction::Column if elem.scroll_left() != scroll => elem.set_scroll_left(scroll),
+            _ => {}
+        }
+    }
+
+    /// Get the size of the window along `direction`.
+    pub(crate) fn window_size(direction: Direction) -> Option<f64> {
+        let window = web_sys::window()?;
+        let size = match direction {
+            Direction::Row => window.inner_height(),
+            Direction::Column => window.inner_width(),
+        };
+        size.ok()?.as_f64()
+    }
+
+    /// Get how far the window has scrolled past the start of `mounted`.
+    pub(crate) fn window_offset(mounted: &MountedData, direction: Direction) -> i32 {
+        let rect = mounted
+            .try_as_web_event()
+            .unwrap()
+            .get_bounding_client_rect();
+        let start = match direction {
+            Direction::Row => rect.top(),
+            Direction::Column => rect.left(),
+        };
+        (-start).max(0.) as i32
+    }
+
+    /// Scroll the window to `offset` past the start of `mounted`.
+    pub(crate) fn scroll_window_to(
+        mounted: &MountedData,
+        direction: Direction,
+        offset: f64,
+        behavior: ScrollBehavior,
+    ) {
+        let window = web_sys::window().unwrap();
+        let rect = mounted
+            .try_as_web_event()
+            .unwrap()
+            .get_bounding_client_rect();
+        let start = match direction {
+            Direction::Row => rect.top() + window.scroll_y().unwrap_or_default(),
+            Direction::Column => rect.left() + window.scroll_x().unwrap_or_default(),
+        };
+        window.scroll_to_with_scroll_to_options(&scroll_options(
+            direction,
+            start + offset,
+            behavior,
+        ));
+    }
+
+    /// Call `f` when the window scrolls or resizes, and whenever `mounted` changes.
+    pub(crate) fn use_window_listener(
+        enabled: bool,
+        mounted: UseMounted,
+        mut f: impl FnMut() + Copy + 'static,
+    ) {
+        let mut listener = use_hook(|| CopyValue::new(None::<Closure<dyn FnMut()>>));
+        use_effect(move || {
+            if !enabled || mounted.signal.read().is_none() {
+                return;
+            }
+            if listener.peek().is_none() {
+                let window = web_sys::window().unwrap();
+                let closure = Closure::<dyn FnMut()>::new(f);
+                let callback = closure.as_ref().unchecked_ref();
+                window
+                    .add_event_listener_with_callback("scroll", callback)
+                    .unwrap();
+                window
+                    .add_event_listener_with_callback("resize", callback)
+                    .unwrap();
+                listener.set(Some(closure));
+            }
+            f();
+        });
+        use_drop(move || {
+            if let Some(closure) = listener.write().take() {
+                let window = web_sys::window().unwrap();
+                let callback = closure.as_ref().unchecked_ref();
+                window
+                    .remove_event_listener_with_callback("scroll", callback)
+                    .ok();
+                window
+                    .remove_event_listener_with_callback("resize", callback)
+                    .ok();
+            }
+        });
+    }
+
+    fn scroll_options(
+        direction: Direction,
+        offset: f64,
+        behavior: ScrollBehavior,
+    ) -> ScrollToOptions {
+        let options = ScrollToOptions::new();
+        match direction {
+            Direction::Row => options.set_top(offset),
+            Direction::Column => options.set_left(offset),
+        }
+        options.set_behavior(match behavior {
+            ScrollBehavior::Instant => web_sys::ScrollBehavior::Instant,
+            ScrollBehavior::Smooth => web_sys::ScrollBehavior::Smooth,
+        });
+        options
+    }
+}
+
+#[cfg(not(feature = "web"))]
+mod native {
+    use super::*;
+
+    /// Use the `(width, height)` of the mounted element.
+    ///
+    /// Elements are measured once they're mounted,
+    /// as other renderers don't report when they resize.
+    pub(crate) fn use_size(mounted: UseMounted) -> Memo<Option<(f64, f64)>> {
+        let mut size = use_signal(|| None);
+        use_effect(move || {
+            let Some(mounted) = mounted.signal.read().clone() else {
+                return;
+            };
+            spawn(async move {
+                if let Ok(rect) = mounted.get_client_rect().await {
+                    size.set(Some((rect.width(), rect.height())));
+                }
+            });
+        });
+        use_memo(move || *size.read())
+    }
+
+    /// Read the `(left, top)` scroll offset of `mounted` and pass it to `f`.
+    pub(crate) fn read_scroll(mounted: Rc<MountedData>, f: impl FnOnce(i32, i32) + 'static) {
+        spawn(async move {
+            if let Ok(offset) = mounted.get_scroll_offset().await {
+                f(offset.x as i32, offset.y as i32)
+            }
+        });
+    }
+
+    /// Scroll `mounted` to `offset` along `direction`.
+    pub(crate) fn scroll_to(
+        _mounted: &MountedData,
+        _direction: Direction,
+        _offset: f64,
+        _behavior: ScrollBehavior,
+    ) {
+        log::warn!("Scrolling to an offset requires the `web` feature");
+    }
+
+    /// Move `mounted` to `scroll` along `direction` if it isn't already there.
+    pub(crate) fn sync_scroll(_mounted: &MountedData, _direction: Direction, _scroll: i32) {}
+
+    /// Get the size of the window along `direction`.
+    pub(crate) fn window_size(_direction: Direction) -> Option<f64> {
+        None
+    }
+
+    /// Get how far the window has scrolled past the start of `mounted`.
+    pub(crate) fn window_offset(_mounted: &MountedData, _direction: Direction) -> i32 {
+        0
+    }
+
+    /// Scroll the window to `offset` past the start of `mounted`.
+    pub(crate) fn scroll_window_to(
+        _mounted: &MountedData,
+        _direction: Direction,
+        _offset: f64,
+        _behavior: ScrollBehavior,
+    ) {
+    }
+
+    /// Call `f` when the window scrolls or resizes, and whenever `mounted` changes.
+    pub(crate) fn use_window_listener(
+        _enabled: bool,
+        _mounted: UseMounted,
+        _f: impl FnMut() + Copy + 'static,
+    ) {
+    }
+}
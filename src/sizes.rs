@@ -0,0 +1,268 @@
+/// Measured item sizes, indexed by prefix sums.
+///
+/// Items start out at an estimated size and are updated as they're measured.
+/// Offsets and lookups by offset run in `O(log n)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sizes {
+    estimate: f64,
+    sizes: Vec<f64>,
+    tree: Vec<f64>,
+}
+
+impl Sizes {
+    /// Create a new index of `len` items of the `estimate` size.
+    pub fn new(len: usize, estimate: f64) -> Self {
+        let mut me = Self {
+            estimate,
+            sizes: vec![estimate; len],
+            tree: Vec::new(),
+        };
+        me.rebuild();
+        me
+    }
+
+    /// Get the number of items.
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns `true` if there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Resize to `len` items, adding new items at the estimated size.
+    pub fn resize(&mut self, len: usize) {
+        self.sizes.resize(len, self.estimate);
+        self.rebuild();
+    }
+
+    /// Insert `count` items at the estimated size before the first item.
+    pub fn prepend(&mut self, count: usize) {
+        self.sizes
+            .splice(0..0, std::iter::repeat_n(self.estimate, count));
+        self.rebuild();
+    }
+
+    /// Insert an item at the estimated size at `idx`.
+    pub fn insert(&mut self, idx: usize) {
+        self.sizes.insert(idx.min(self.len()), self.estimate);
+        self.rebuild();
+    }
+
+    /// Remove the item at `idx`.
+    pub fn remove(&mut self, idx: usize) {
+        if idx < self.len() {
+            self.sizes.remove(idx);
+            self.rebuild();
+        }
+    }
+
+    /// Get the estimated size of unmeasured items.
+    pub fn estimate(&self) -> f64 {
+        self.estimate
+    }
+
+    /// Get the size of the item at `idx`.
+    pub fn get(&self, idx: usize) -> f64 {
+        self.sizes.get(idx).copied().unwrap_or(self.estimate)
+    }
+
+    /// Set the measured size of the item at `idx`.
+    pub fn set(&mut self, idx: usize, size: f64) {
+        let Some(last) = self.sizes.get_mut(idx) else {
+            return;
+        };
+        let delta = size - *last;
+        *last = size;
+
+        let mut pos = idx + 1;
+        while pos < self.tree.len() {
+            self.tree[pos] += delta;
+            pos += pos & pos.wrapping_neg();
+        }
+    }
+
+    /// Get the offset of the start of the item at `idx`.
+    pub fn offset(&self, idx: usize) -> f64 {
+        let mut pos = idx.min(self.len());
+        let mut offset = 0.;
+        while pos > 0 {
+            offset += self.tree[pos];
+            pos -= pos & pos.wrapping_neg();
+        }
+        offset
+    }
+
+    /// Get the total size of all items.
+    pub fn total(&self) -> f64 {
+        self.offset(self.len())
+    }
+
+    /// Get the index of the item containing `offset`.
+    ///
+    /// Offsets past the end return the number of items.
+    pub fn index_at(&self, offset: f64) -> usize {
+        let mut pos = 0;
+        let mut remaining = offset;
+        let mut step = self.len().checked_next_power_of_two().unwrap_or(0);
+        while step > 0 {
+            let next = pos + step;
+            if next <= self.len() && self.tree[next] <= remaining {
+                pos = next;
+                remaining -= self.tree[next];
+            }
+            step /= 2;
+        }
+        pos
+    }
+
+    fn rebuild(&mut self) {
+        self.tree = vec![0.; self.sizes.len() + 1];
+        for (idx, size) in self.sizes.iter().enumerate() {
+            let pos = idx + 1;
+            self.tree[pos] += size;
+
+            let parent = pos + (pos & pos.wrapping_neg());
+            if parent < self.tree.len() {
+                self.tree[parent] += self.tree[pos];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sizes;
+
+    fn sizes(values: &[f64]) -> Sizes {
+        let mut sizes = Sizes::new(values.len(), 20.);
+        for (idx, size) in values.iter().enumerate() {
+            sizes.set(idx, *size);
+        }
+        sizes
+    }
+
+    #[test]
+    fn offsets_are_prefix_sums() {
+        let sizes = sizes(&[10., 20., 30., 40., 50.]);
+
+        let offsets: Vec<_> = (0..=5).map(|idx| sizes.offset(idx)).collect();
+        assert_eq!(offsets, [0., 10., 30., 60., 100., 150.]);
+        assert_eq!(sizes.offset(10), 150.);
+        assert_eq!(sizes.total(), 150.);
+    }
+
+    #[test]
+    fn set_updates_later_offsets() {
+        let mut sizes = Sizes::new(8, 20.);
+        assert_eq!(sizes.total(), 160.);
+
+        sizes.set(3, 50.);
+        assert_eq!(sizes.offset(3), 60.);
+        assert_eq!(sizes.offset(4), 110.);
+        assert_eq!(sizes.total(), 190.);
+
+        sizes.set(3, 10.);
+        sizes.set(7, 0.);
+        assert_eq!(sizes.offset(4), 70.);
+        assert_eq!(sizes.total(), 130.);
+
+        // Out of bounds indices are ignored.
+        sizes.set(8, 100.);
+        assert_eq!(sizes.total(), 130.);
+    }
+
+    #[test]
+    fn index_at_item_boundaries() {
+        let sizes = sizes(&[10., 20., 30.]);
+
+        assert_eq!(sizes.index_at(0.), 0);
+        assert_eq!(sizes.index_at(9.9), 0);
+        assert_eq!(sizes.index_at(10.), 1);
+        assert_eq!(sizes.index_at(29.9), 1);
+        assert_eq!(sizes.index_at(30.), 2);
+        assert_eq!(sizes.index_at(59.9), 2);
+    }
+
+    #[test]
+    fn index_at_past_the_end() {
+        let sizes = sizes(&[10., 20., 30.]);
+        assert_eq!(sizes.index_at(60.), 3);
+        assert_eq!(sizes.index_at(1000.), 3);
+
+        let empty = Sizes::new(0, 20.);
+        assert_eq!(empty.index_at(0.), 0);
+        assert_eq!(empty.index_at(100.), 0);
+    }
+
+    #[test]
+    fn index_at_matches_offsets() {
+        let sizes = sizes(&[5., 15., 25., 35., 45., 55., 65.]);
+        for idx in 0..sizes.len() {
+            assert_eq!(sizes.index_at(sizes.offset(idx)), idx);
+            assert_eq!(sizes.index_at(sizes.offset(idx + 1) - 0.5), idx);
+        }
+    }
+
+    #[test]
+    fn resize_keeps_measured_sizes() {
+        let mut sizes = sizes(&[10., 30.]);
+        sizes.resize(4);
+        assert_eq!(sizes.total(), 80.);
+
+        sizes.resize(1);
+        assert_eq!(sizes.total(), 10.);
+    }
+
+    #[test]
+    fn prepend_rebuilds() {
+        let mut sizes = sizes(&[10., 30.]);
+        sizes.prepend(2);
+
+        assert_eq!(sizes.len(), 4);
+        assert_eq!(sizes.get(0), 20.);
+        assert_eq!(sizes.get(2), 10.);
+        assert_eq!(sizes.offset(2), 40.);
+        assert_eq!(sizes.total(), 80.);
+        assert_eq!(sizes.index_at(45.), 2);
+        assert_eq!(sizes.index_at(50.), 3);
+    }
+
+    #[test]
+    fn insert_rebuilds() {
+        let mut sizes = sizes(&[10., 30., 50.]);
+        sizes.insert(1);
+
+        assert_eq!(sizes.len(), 4);
+        assert_eq!(sizes.get(1), 20.);
+        assert_eq!(sizes.offset(2), 30.);
+        assert_eq!(sizes.total(), 110.);
+        assert_eq!(sizes.index_at(30.), 2);
+
+        // Indices past the end insert at the end.
+        sizes.insert(10);
+        assert_eq!(sizes.len(), 5);
+        assert_eq!(sizes.total(), 130.);
+    }
+
+    #[test]
+    fn remove_rebuilds() {
+        let mut sizes = sizes(&[10., 30., 50.]);
+        sizes.remove(1);
+
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes.get(1), 50.);
+        assert_eq!(sizes.offset(1), 10.);
+        assert_eq!(sizes.total(), 60.);
+        assert_eq!(sizes.index_at(10.), 1);
+
+        // Out of bounds indices are ignored.
+        sizes.remove(2);
+        assert_eq!(sizes.len(), 2);
+
+        // Measured sizes can still be set after a rebuild.
+        sizes.set(0, 40.);
+        assert_eq!(sizes.total(), 90.);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    lazy::{Lazy, Values},
+    platform,
+    use_scroll_range::use_effect_signal,
+    Direction, UseScrollRange,
+};
+use dioxus::prelude::*;
+use dioxus_use_mounted::{use_mounted, UseMounted};
+use std::{marker::PhantomData, ops::Range};
+
+struct Inner {
+    len: usize,
+    columns: usize,
+    width: f64,
+    height: f64,
+    cell_width: f64,
+    cell_height: f64,
+    min_cell_width: Option<f64>,
+}
+
+pub struct Builder<F> {
+    inner: Option<Inner>,
+    _marker: PhantomData<F>,
+}
+
+impl<F> Builder<F> {
+    pub fn len(&mut self, len: usize) -> &mut Self {
+        self.inner.as_mut().unwrap().len = len;
+        self
+    }
+
+    /// Number of columns in each row.
+    pub fn columns(&mut self, columns: usize) -> &mut Self {
+        self.inner.as_mut().unwrap().columns = columns;
+        self
+    }
+
+    pub fn width(&mut self, width: f64) -> &mut Self {
+        self.inner.as_mut().unwrap().width = width;
+        self
+    }
+
+    pub fn height(&mut self, height: f64) -> &mut Self {
+        self.inner.as_mut().unwrap().height = height;
+        self
+    }
+
+    pub fn cell_width(&mut self, cell_width: f64) -> &mut Self {
+        self.inner.as_mut().unwrap().cell_width = cell_width;
+        self
+    }
+
+    pub fn cell_height(&mut self, cell_height: f64) -> &mut Self {
+        self.inner.as_mut().unwrap().cell_height = cell_height;
+        self
+    }
+
+    /// Derive the number of columns from the container's measured width,
+    /// fitting as many cells of at least `min_cell_width` as possible.
+    ///
+    /// Cells are stretched to fill each row, and the grid reflows when the container resizes.
+    pub fn min_cell_width(&mut self, min_cell_width: Option<f64>) -> &mut Self {
+        self.inner.as_mut().unwrap().min_cell_width = min_cell_width;
+        self
+    }
+
+    pub fn use_grid(&mut self, make_value: F) -> UseGrid<F::Values>
+    where
+        F: Lazy,
+    {
+        let mounted = use_mounted();
+        let element_size = platform::use_size(mounted);
+
+        let inner = self.inner.take().unwrap();
+        let cell_count = make_value.known_len().unwrap_or(inner.len);
+        let lazy = make_value.values();
+
+        let measured_width = element_size().map(|(width, _)| width);
+        let (columns, width, cell_width) = match (inner.min_cell_width, measured_width) {
+            (Some(min_cell_width), Some(width)) => {
+                let columns = ((width / min_cell_width).floor() as usize).max(1);
+                (columns, width, width / columns as f64)
+            }
+            _ => (inner.columns.max(1), inner.width, inner.cell_width),
+        };
+        let len = use_effect_signal(cell_count);
+        let columns_signal = use_effect_signal(columns);
+
+        // Values are requested a whole row at a time, in row-major order.
+        let mut lazy_clone = lazy.clone();
+        let row_range = UseScrollRange::builder()
+            .direction(Direction::Row)
+            .size(inner.height)
+            .item_size(inner.cell_height)
+            .len(cell_count.div_ceil(columns))
+            .use_scroll_range(move |rows| {
+                let columns = columns_signal();
+                lazy_clone.set(rows.start * columns..(rows.end * columns).min(len()))
+            });
+
+        let column_range = UseScrollRange::builder()
+            .direction(Direction::Column)
+            .size(width)
+            .item_size(cell_width)
+            .len(columns)
+            .use_scroll_range(|_| {});
+
+        UseGrid {
+            mounted,
+            row_range,
+            column_range,
+            columns: columns_signal,
+            len,
+            lazy,
+        }
+    }
+}
+
+/// Virtualized grid of fixed-size cells.
+pub struct UseGrid<T: 'static> {
+    pub mounted: UseMounted,
+
+    /// Scroll range of the visible rows.
+    pub row_range: UseScrollRange,
+
+    /// Scroll range of the visible columns.
+    pub column_range: UseScrollRange,
+
+    /// Number of columns in each row.
+    pub columns: Signal<usize>,
+
+    /// Number of cells.
+    pub len: Signal<usize>,
+
+    pub lazy: T,
+}
+
+impl<T> UseGrid<T> {
+    pub fn builder() -> Builder<T> {
+        Builder {
+            inner: Some(Inner {
+                len: 0,
+                columns: 1,
+                width: 400.,
+                height: 400.,
+                cell_width: 100.,
+                cell_height: 100.,
+                min_cell_width: None,
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn scroll(&mut self) {
+        let Some(mounted) = self.mounted.signal.read().clone() else {
+            return;
+        };
+        let mut row_range = self.row_range;
+        let mut column_range = self.column_range;
+        platform::read_scroll(mounted, move |left, top| {
+            row_range.set_scroll(top);
+            column_range.set_scroll(left);
+        });
+    }
+
+    /// Get the indices of the visible cells, in row-major order.
+    pub fn cells(&self) -> Vec<Range<usize>> {
+        let columns = *self.columns.read();
+        let len = *self.len.read();
+        let column_range = self.column_range.range();
+
+        self.row_range
+            .range()
+            .map(|row| {
+                let start = (row * columns + column_range.start).min(len);
+                let end = (row * columns + column_range.end).min(len);
+                start..end
+            })
+            .collect()
+    }
+}
+
+impl<T: Clone> Clone for UseGrid<T> {
+    fn clone(&self) -> Self {
+        Self {
+            mounted: self.mounted,
+            row_range: self.row_range,
+            column_range: self.column_range,
+            columns: self.columns,
+            len: self.len,
+            lazy: self.lazy.clone(),
+        }
+    }
+}
+
+impl<T: Copy> Copy for UseGrid<T> {}
+
+impl<T: PartialEq> PartialEq for UseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.mounted == other.mounted
+            && self.row_range == other.row_range
+            && self.column_range == other.column_range
+            && self.columns == other.columns
+            && self.len == other.len
+            && self.lazy == other.lazy
+    }
+}
@@ -1,6 +1,6 @@
 use crate::lazy::Values;
 use dioxus::prelude::*;
-use std::{cmp::Ordering, collections::VecDeque, ops::Range};
+use std::{cmp::Ordering, collections::VecDeque, convert::Infallible, ops::Range};
 
 pub fn use_lazy<F, V, I>(make_value: F) -> UseLazy<F, V>
 where
@@ -29,15 +29,26 @@ where
     I: IntoIterator<Item = V>,
 {
     type Value = V;
+    type Error = Infallible;
 
     fn values(&self) -> Signal<VecDeque<Self::Value>> {
         self.values
     }
 
+    fn range(&self) -> Signal<Range<usize>> {
+        self.range
+    }
+
     fn set(&mut self, range: Range<usize>) {
         let mut last = self.range.write();
         let mut values = self.values;
 
+        // Start over from an empty range if the new range doesn't overlap the last one.
+        if range.start >= last.end || range.end <= last.start {
+            values.write().clear();
+            *last = range.start..range.start;
+        }
+
         match range.start.cmp(&last.start) {
             Ordering::Less => {
                 let mut rows_ref = values.write();
@@ -87,6 +98,28 @@ where
             values_ref.push_back(value);
         }
     }
+
+    fn invalidate_range(&mut self, range: Range<usize>) {
+        let last = self.range.peek().clone();
+        let start = range.start.max(last.start);
+        let end = range.end.min(last.end);
+        if start >= end {
+            return;
+        }
+
+        let values = (self.make_value).write()(start..end, false);
+        let mut values_ref = self.values.write();
+        for (idx, value) in (start..end).zip(values) {
+            if let Some(slot) = values_ref.get_mut(idx - last.start) {
+                *slot = value;
+            }
+        }
+    }
+
+    fn shift(&mut self, by: usize) {
+        let mut range = self.range.write();
+        *range = range.start + by..range.end + by;
+    }
 }
 
 impl<F, V> Clone for UseLazy<F, V> {
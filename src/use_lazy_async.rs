@@ -1,100 +1,472 @@
-use crate::{lazy::Values, Factory};
+use crate::{
+    lazy::{self, RangeError, Values},
+    TryFactory,
+};
 use dioxus::prelude::*;
-use futures::StreamExt;
-use std::{cmp::Ordering, collections::VecDeque, ops::Range, rc::Rc};
+use futures::{
+    channel::mpsc::TryRecvError,
+    future::{self, Either, OptionFuture},
+    StreamExt,
+};
+use std::{collections::VecDeque, convert::Infallible, ops::Range, pin::pin, rc::Rc};
+
+#[cfg(feature = "fullstack")]
+use crate::lazy::Hydrate;
 
 enum Message {
     Range(Range<usize>),
     Refresh,
+    Invalidate(Range<usize>),
+    Reload(Range<usize>),
+    Retry,
+    Shift(usize),
+    Insert(usize),
+    Remove(usize),
+}
+
+pub fn use_lazy_async<F>(make_value: F) -> UseLazyAsync<F::Item, F::Error>
+where
+    F: TryFactory + 'static,
+{
+    use_lazy_async_inner(Rc::new(make_value), |_, _| None)
+}
+
+/// Use values from `make_value`, loading the `initial` range on the server
+/// and sending it along with the page, so the client hydrates with the same values.
+#[cfg(feature = "fullstack")]
+pub fn use_lazy_async_hydrated<F>(
+    make_value: F,
+    initial: Range<usize>,
+) -> UseLazyAsync<F::Item, F::Error>
+where
+    F: TryFactory + 'static,
+    F::Item: Hydrate,
+{
+    use_lazy_async_inner(Rc::new(make_value), move |state, make_value| {
+        use_initial(state, make_value, initial)
+    })
 }
 
-pub fn use_lazy_async<F>(make_value: F) -> UseLazyAsync<F::Item>
+/// Use values from `make_value`, starting with the state from `use_initial`.
+///
+/// `use_initial` returns the error to suspend with until the initial values are ready.
+fn use_lazy_async_inner<F>(
+    make_value: Rc<F>,
+    use_initial: impl FnOnce(State<F::Item, F::Error>, Rc<F>) -> Option<RenderError>,
+) -> UseLazyAsync<F::Item, F::Error>
 where
-    F: Factory + 'static,
+    F: TryFactory + 'static,
 {
-    let mut values = use_signal(VecDeque::new);
+    let state = State {
+        values: use_signal(VecDeque::new),
+        errors: use_signal(Vec::new),
+        range: use_signal(|| 0..0),
+        pending: use_signal(Vec::new),
+    };
 
-    let last = 0..0;
-    let make_value = Rc::new(make_value);
+    let mut suspended = use_hook(|| CopyValue::new(None));
+    suspended.set(use_initial(state, make_value.clone()));
 
+    let factory = make_value.clone();
     let task = use_coroutine(move |mut rx| {
-        let mut last = last.clone();
         let make_value = make_value.clone();
 
         async move {
-            while let Some(msg) = rx.next().await {
-                match msg {
-                    Message::Range(range) => {
-                        match range.start.cmp(&last.start) {
-                            Ordering::Less => {
-                                let mut rows_ref = values.write();
-                                let values = make_value.make(range.start..last.start, true).await;
-                                for value in values.into_iter() {
-                                    rows_ref.push_front(value);
-                                }
+            let mut state = state;
+            let mut requested = state.range.peek().clone();
+            let mut is_refresh = false;
+            let mut invalidated = Vec::new();
+            let mut queued = Vec::new();
+
+            loop {
+                if queued.is_empty() {
+                    match rx.next().await {
+                        Some(msg) => queued.push(msg),
+                        None => break,
+                    }
+                }
+
+                // Collapse every queued message into the latest requested state.
+                loop {
+                    match rx.try_recv() {
+                        Ok(msg) => queued.push(msg),
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Closed) => return,
+                    }
+                }
+                for msg in queued.drain(..) {
+                    match msg {
+                        Message::Range(range) => requested = range,
+                        Message::Refresh => is_refresh = true,
+                        Message::Invalidate(range) => {
+                            make_value.invalidate(range.clone());
+                            invalidated.push(range);
+                        }
+                        Message::Reload(range) => invalidated.push(range),
+                        Message::Retry => {
+                            // Ranges that failed to reload are still in view, so load them again.
+                            let range = state.range.peek().clone();
+                            let mut errors = state.errors.write();
+                            invalidated.extend(
+                                errors
+                                    .iter()
+                                    .map(|error| error.range.clone())
+                                    .filter(|error| {
+                                        error.start < range.end && range.start < error.end
+                                    }),
+                            );
+                            errors.clear();
+                        }
+                        Message::Shift(by) => {
+                            make_value.invalidate(0..usize::MAX);
+                            let shift = |range: &Range<usize>| range.start + by..range.end + by;
+                            requested = shift(&requested);
+                            for range in &mut invalidated {
+                                *range = shift(range);
                             }
-                            Ordering::Greater => {
-                                let mut rows_ref = values.write();
-                                for _ in 0..range.start - last.start {
-                                    rows_ref.pop_front();
-                                }
+                        }
+                        Message::Insert(idx) => {
+                            make_value.invalidate(idx..usize::MAX);
+                            requested = if idx < requested.start {
+                                requested.start + 1..requested.end + 1
+                            } else if idx <= requested.end {
+                                requested.start..requested.end + 1
+                            } else {
+                                requested
+                            };
+                            for range in &mut invalidated {
+                                *range = lazy::insert_index(range, idx);
                             }
-                            Ordering::Equal => {}
                         }
-
-                        if range.start != range.end {
-                            match range.end.cmp(&last.end) {
-                                Ordering::Greater => {
-                                    let mut rows_ref = values.write();
-                                    let values = make_value.make(last.end..range.end, false).await;
-                                    for value in values.into_iter() {
-                                        rows_ref.push_back(value);
-                                    }
-                                }
-                                Ordering::Less => {
-                                    let mut rows_ref = values.write();
-                                    for _ in 0..last.end - range.end {
-                                        rows_ref.pop_back();
-                                    }
-                                }
-                                Ordering::Equal => {}
+                        Message::Remove(idx) => {
+                            make_value.invalidate(idx..usize::MAX);
+                            requested = lazy::remove_index(&requested, idx);
+                            for range in &mut invalidated {
+                                *range = lazy::remove_index(range, idx);
                             }
                         }
+                    }
+                }
 
-                        last = range;
+                let mut task = pin!(async {
+                    if is_refresh {
+                        state.refresh(&*make_value).await;
+                        is_refresh = false;
+                        invalidated.clear();
+                    }
+                    if !invalidated.is_empty() {
+                        state.revalidate(&*make_value, &invalidated).await;
+                        invalidated.clear();
                     }
-                    Message::Refresh => {
-                        let mut rows_ref = values.write();
-                        rows_ref.clear();
+                    state.load(&*make_value, requested.clone()).await;
+                });
 
-                        let values = make_value.make(last.clone(), false).await;
-                        for value in values.into_iter() {
-                            rows_ref.push_back(value);
+                // Keep the in-flight request while it's still in view, otherwise drop it.
+                loop {
+                    match future::select(task.as_mut(), rx.next()).await {
+                        Either::Left(_) => break,
+                        Either::Right((Some(Message::Range(range)), _))
+                            if range.start < requested.end && requested.start < range.end =>
+                        {
+                            queued.retain(|msg| !matches!(msg, Message::Range(_)));
+                            queued.push(Message::Range(range));
                         }
+                        Either::Right((Some(Message::Retry), _)) => queued.push(Message::Retry),
+                        Either::Right((Some(msg @ Message::Invalidate(_)), _)) => queued.push(msg),
+                        Either::Right((Some(msg @ Message::Reload(_)), _)) => queued.push(msg),
+                        Either::Right((Some(msg), _)) => {
+                            queued.push(msg);
+                            break;
+                        }
+                        Either::Right((None, _)) => return,
                     }
                 }
             }
         }
     });
 
+    // Reload values invalidated by other lists sharing the same cache.
+    use_hook(move || {
+        factory
+            .subscribe(Rc::new(move |range| task.send(Message::Reload(range))))
+            .map(Rc::new)
+    });
+
     UseLazyAsync {
         task: CopyValue::new(task),
-        values,
+        values: state.values,
+        errors: state.errors,
+        range: state.range,
+        pending: state.pending,
+        suspended,
+    }
+}
+
+/// Load the `initial` range on the server, or take it from the server's data when hydrating.
+///
+/// Returns the error to suspend with until the values are ready,
+/// which the list returns once the rest of its hooks are created.
+#[cfg(feature = "fullstack")]
+fn use_initial<F>(
+    mut state: State<F::Item, F::Error>,
+    make_value: Rc<F>,
+    initial: Range<usize>,
+) -> Option<RenderError>
+where
+    F: TryFactory + 'static,
+    F::Item: Hydrate,
+{
+    // Every hook is created before suspending, so they're in the same order on the next render.
+    let mut is_applied = use_hook(|| CopyValue::new(false));
+
+    let range = initial.clone();
+    let resource = use_server_future(move || {
+        let make_value = make_value.clone();
+        let range = range.clone();
+        async move {
+            if range.is_empty() {
+                return None;
+            }
+
+            // Failed ranges are left for the client to load and report.
+            let values = make_value.try_make(range, false).await.ok()?;
+            Some(values.into_iter().collect::<Vec<_>>())
+        }
+    });
+    let resource = match resource {
+        Ok(resource) => resource,
+        Err(error) => return Some(error),
+    };
+
+    if !*is_applied.peek() {
+        if let Some(Some(values)) = &*resource.value().peek() {
+            is_applied.set(true);
+            state.values.set(values.iter().cloned().collect());
+            state.range.set(initial.start..initial.start + values.len());
+        }
+    }
+    None
+}
+
+struct State<V: 'static, E: 'static> {
+    values: Signal<VecDeque<V>>,
+    errors: Signal<Vec<RangeError<E>>>,
+    range: Signal<Range<usize>>,
+    pending: Signal<Vec<Range<usize>>>,
+}
+
+impl<V, E> Clone for State<V, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V, E> Copy for State<V, E> {}
+
+impl<V, E> State<V, E> {
+    /// Reload the values for the current range.
+    ///
+    /// The current values stay in place until the new ones are ready.
+    async fn refresh<F>(&mut self, make_value: &F)
+    where
+        F: TryFactory<Item = V, Error = E>,
+    {
+        let range = self.range.peek().clone();
+        self.pending.set(vec![range.clone()]);
+
+        make_value.invalidate(0..usize::MAX);
+        let result = make_value.try_make(range.clone(), false).await;
+        if *self.range.peek() != range {
+            return;
+        }
+
+        self.pending.write().clear();
+        match result {
+            Ok(new_values) => {
+                let mut rows_ref = self.values.write();
+                rows_ref.clear();
+                rows_ref.extend(new_values.into_iter().take(range.len()));
+                self.range.set(range.start..range.start + rows_ref.len());
+            }
+            Err(error) => {
+                self.values.write().clear();
+                self.errors
+                    .write()
+                    .push(RangeError::new(range.clone(), error));
+                self.range.set(range.start..range.start);
+            }
+        }
+    }
+
+    /// Reload the values in each of `ranges` that's within the current range.
+    ///
+    /// The current values stay in place until the new ones are ready.
+    /// Ranges that fail to reload keep their current values and are recorded in `errors`.
+    async fn revalidate<F>(&mut self, make_value: &F, ranges: &[Range<usize>])
+    where
+        F: TryFactory<Item = V, Error = E>,
+    {
+        let current = self.range.peek().clone();
+        let fetches: Vec<_> = ranges
+            .iter()
+            .map(|range| range.start.max(current.start)..range.end.min(current.end))
+            .filter(|fetch| !fetch.is_empty())
+            .collect();
+        if fetches.is_empty() {
+            return;
+        }
+        self.pending.write().extend(fetches.iter().cloned());
+
+        let results = future::join_all(
+            fetches
+                .iter()
+                .map(|fetch| make_value.try_make(fetch.clone(), false)),
+        )
+        .await;
+
+        // The current range was shifted while fetching, so these indices are stale.
+        if *self.range.peek() != current {
+            return;
+        }
+
+        self.pending
+            .write()
+            .retain(|range| !fetches.contains(range));
+        let mut values_ref = self.values.write();
+        for (fetch, result) in fetches.into_iter().zip(results) {
+            match result {
+                Ok(new_values) => {
+                    for (idx, value) in fetch.clone().zip(new_values) {
+                        if let Some(slot) = values_ref.get_mut(idx - current.start) {
+                            *slot = value;
+                        }
+                    }
+                }
+                Err(error) => self.errors.write().push(RangeError::new(fetch, error)),
+            }
+        }
+    }
+
+    /// Load the values for `range`, starting from the current range.
+    ///
+    /// Both edges are fetched before any values change, so readers only ever see whole
+    /// updates and the previous values stay in place in the meantime.
+    /// Ranges that fail to load are recorded in `errors` and left out of the current range.
+    /// They aren't requested again until they're retried or scrolled out of view.
+    async fn load<F>(&mut self, make_value: &F, range: Range<usize>)
+    where
+        F: TryFactory<Item = V, Error = E>,
+    {
+        self.errors
+            .write()
+            .retain(|error| error.range.start < range.end && range.start < error.range.end);
+        let errors = self.errors;
+        let is_failed = move |fetch: &Range<usize>| {
+            errors
+                .peek()
+                .iter()
+                .any(|error| error.range.start <= fetch.start && fetch.end <= error.range.end)
+        };
+
+        // Start over from an empty range if the new range doesn't overlap the last one.
+        let current = self.range.peek().clone();
+        let is_disjoint = range.start >= current.end || range.end <= current.start;
+        let last = if is_disjoint {
+            range.start..range.start
+        } else {
+            current.clone()
+        };
+
+        let front =
+            Some(range.start..last.start).filter(|fetch| !fetch.is_empty() && !is_failed(fetch));
+        let back = Some(last.end..range.end)
+            .filter(|fetch| range.start != range.end && !fetch.is_empty() && !is_failed(fetch));
+        self.pending
+            .set(front.iter().chain(back.iter()).cloned().collect());
+
+        let (front_values, back_values) = future::join(
+            OptionFuture::from(front.clone().map(|fetch| make_value.try_make(fetch, true))),
+            OptionFuture::from(back.clone().map(|fetch| make_value.try_make(fetch, false))),
+        )
+        .await;
+
+        // The current range was shifted while fetching, so these indices are stale.
+        if *self.range.peek() != current {
+            return;
+        }
+
+        self.pending.write().clear();
+        let mut rows_ref = self.values.write();
+        let mut next = last;
+        if is_disjoint {
+            rows_ref.clear();
+        }
+
+        if range.start > next.start {
+            for _ in 0..range.start - next.start {
+                rows_ref.pop_front();
+            }
+            next.start = range.start;
+        }
+        match front.zip(front_values) {
+            Some((fetch, Ok(new_values))) => {
+                // Factories can return fewer values than requested, so only count those received.
+                for value in new_values.into_iter().take(fetch.len()) {
+                    rows_ref.push_front(value);
+                    next.start -= 1;
+                }
+            }
+            Some((fetch, Err(error))) => self.errors.write().push(RangeError::new(fetch, error)),
+            None => {}
+        }
+
+        if range.start != range.end && range.end < next.end {
+            for _ in 0..next.end - range.end {
+                rows_ref.pop_back();
+            }
+            next.end = range.end;
+        }
+        match back.zip(back_values) {
+            Some((fetch, Ok(new_values))) => {
+                for value in new_values.into_iter().take(fetch.len()) {
+                    rows_ref.push_back(value);
+                    next.end += 1;
+                }
+            }
+            Some((fetch, Err(error))) => self.errors.write().push(RangeError::new(fetch, error)),
+            None => {}
+        }
+
+        self.range.set(next);
     }
 }
 
-pub struct UseLazyAsync<V: 'static> {
+pub struct UseLazyAsync<V: 'static, E: 'static = Infallible> {
     pub values: Signal<VecDeque<V>>,
+    pub errors: Signal<Vec<RangeError<E>>>,
+
+    /// Range of indices currently held in `values`.
+    pub range: Signal<Range<usize>>,
+
+    /// Ranges of indices currently being fetched.
+    pub pending: Signal<Vec<Range<usize>>>,
+
     task: CopyValue<Coroutine<Message>>,
+    suspended: CopyValue<Option<RenderError>>,
 }
 
-impl<V> Values for UseLazyAsync<V> {
+impl<V, E> Values for UseLazyAsync<V, E> {
     type Value = V;
+    type Error = E;
 
     fn values(&self) -> Signal<VecDeque<Self::Value>> {
         self.values
     }
 
+    fn range(&self) -> Signal<Range<usize>> {
+        self.range
+    }
+
     fn set(&mut self, range: Range<usize>) {
         self.task.read().send(Message::Range(range))
     }
@@ -102,17 +474,80 @@ impl<V> Values for UseLazyAsync<V> {
     fn refresh(&mut self) {
         self.task.read().send(Message::Refresh)
     }
+
+    fn errors(&self) -> Option<Signal<Vec<RangeError<Self::Error>>>> {
+        Some(self.errors)
+    }
+
+    fn invalidate_range(&mut self, range: Range<usize>) {
+        self.task.read().send(Message::Invalidate(range))
+    }
+
+    fn retry(&mut self) {
+        self.task.read().send(Message::Retry)
+    }
+
+    fn pending(&self) -> Option<Signal<Vec<Range<usize>>>> {
+        Some(self.pending)
+    }
+
+    fn suspend(&self) -> Result<(), RenderError> {
+        match self.suspended.peek().clone() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn shift(&mut self, by: usize) {
+        let shift = |range: &Range<usize>| range.start + by..range.end + by;
+
+        let next = shift(&self.range.peek());
+        self.range.set(next);
+        for range in self.pending.write().iter_mut() {
+            *range = shift(range);
+        }
+        for error in self.errors.write().iter_mut() {
+            error.range = shift(&error.range);
+        }
+
+        self.task.read().send(Message::Shift(by))
+    }
+
+    fn insert(&mut self, idx: usize, value: Self::Value) {
+        lazy::insert_value(self.values, self.range, idx, value);
+        for range in self.pending.write().iter_mut() {
+            *range = lazy::insert_index(range, idx);
+        }
+        for error in self.errors.write().iter_mut() {
+            error.range = lazy::insert_index(&error.range, idx);
+        }
+
+        self.task.read().send(Message::Insert(idx))
+    }
+
+    fn remove(&mut self, idx: usize) -> Option<Self::Value> {
+        let value = lazy::remove_value(self.values, self.range, idx);
+        for range in self.pending.write().iter_mut() {
+            *range = lazy::remove_index(range, idx);
+        }
+        for error in self.errors.write().iter_mut() {
+            error.range = lazy::remove_index(&error.range, idx);
+        }
+
+        self.task.read().send(Message::Remove(idx));
+        value
+    }
 }
 
-impl<V> Clone for UseLazyAsync<V> {
+impl<V, E> Clone for UseLazyAsync<V, E> {
     fn clone(&self) -> Self {
         *self
     }
 }
 
-impl<V> Copy for UseLazyAsync<V> {}
+impl<V, E> Copy for UseLazyAsync<V, E> {}
 
-impl<V> PartialEq for UseLazyAsync<V> {
+impl<V, E> PartialEq for UseLazyAsync<V, E> {
     fn eq(&self, other: &Self) -> bool {
         self.values == other.values && self.task == other.task
     }
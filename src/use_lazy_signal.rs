@@ -0,0 +1,124 @@
+use crate::lazy::Values;
+use dioxus::prelude::*;
+use std::{collections::VecDeque, convert::Infallible, ops::Range};
+
+/// Use the values of `data` in the current range,
+/// taken again whenever `data` changes.
+pub fn use_lazy_signal<T>(data: Signal<Vec<T>>) -> UseLazySignal<T>
+where
+    T: Clone + 'static,
+{
+    let values = use_signal(VecDeque::new);
+    let range = use_signal(|| 0..0);
+
+    let mut lazy = UseLazySignal {
+        data,
+        values,
+        range,
+    };
+    use_effect(move || {
+        data.read();
+        lazy.refresh();
+    });
+
+    lazy
+}
+
+pub struct UseLazySignal<T: 'static> {
+    pub data: Signal<Vec<T>>,
+    pub values: Signal<VecDeque<T>>,
+    range: Signal<Range<usize>>,
+}
+
+impl<T> Values for UseLazySignal<T>
+where
+    T: Clone + 'static,
+{
+    type Value = T;
+    type Error = Infallible;
+
+    fn values(&self) -> Signal<VecDeque<Self::Value>> {
+        self.values
+    }
+
+    fn range(&self) -> Signal<Range<usize>> {
+        self.range
+    }
+
+    fn set(&mut self, range: Range<usize>) {
+        if *self.range.peek() != range {
+            self.range.set(range);
+        }
+        self.refresh();
+    }
+
+    /// Take the values in the current range from the data again,
+    /// leaving out any indices past its end.
+    fn refresh(&mut self) {
+        let data = self.data.peek();
+        let range = self.range.peek().clone();
+        let end = range.end.min(data.len());
+        let start = range.start.min(end);
+
+        self.values.set(data[start..end].iter().cloned().collect());
+        if range != (start..end) {
+            self.range.set(start..end);
+        }
+    }
+
+    fn invalidate_range(&mut self, range: Range<usize>) {
+        let data = self.data.peek();
+        let last = self.range.peek().clone();
+        let start = range.start.max(last.start);
+        let end = range.end.min(last.end).min(data.len());
+
+        let mut values_ref = self.values.write();
+        for idx in start..end {
+            if let Some(slot) = values_ref.get_mut(idx - last.start) {
+                *slot = data[idx].clone();
+            }
+        }
+    }
+
+    /// Update the value at `idx` in `data`.
+    fn update(&mut self, idx: usize, f: impl FnOnce(&mut Self::Value)) {
+        if let Some(value) = self.data.write().get_mut(idx) {
+            f(value);
+        }
+    }
+
+    /// Insert `value` at `idx` in `data`.
+    fn insert(&mut self, idx: usize, value: Self::Value) {
+        let mut data = self.data.write();
+        let idx = idx.min(data.len());
+        data.insert(idx, value);
+    }
+
+    /// Remove the value at `idx` from `data`.
+    fn remove(&mut self, idx: usize) -> Option<Self::Value> {
+        let mut data = self.data.write();
+        (idx < data.len()).then(|| data.remove(idx))
+    }
+
+    fn shift(&mut self, by: usize) {
+        let next = {
+            let range = self.range.peek();
+            range.start + by..range.end + by
+        };
+        self.range.set(next);
+    }
+}
+
+impl<T> Clone for UseLazySignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for UseLazySignal<T> {}
+
+impl<T> PartialEq for UseLazySignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.values == other.values
+    }
+}
@@ -1,16 +1,26 @@
 use crate::{
     lazy::{Lazy, Values},
-    Direction, UseScrollRange,
+    platform,
+    use_scroll_range::use_effect_signal,
+    Align, Direction, Overscan, Size, UseScrollRange,
 };
-use dioxus::{prelude::*, web::WebEventExt};
+use dioxus::prelude::*;
 use dioxus_use_mounted::{use_mounted, UseMounted};
 use std::marker::PhantomData;
 
 struct Inner {
     direction: Direction,
     len: usize,
-    size: f64,
+    size: Size,
     item_size: f64,
+    measure_items: bool,
+    overscan: Overscan,
+    overscan_ahead: Option<Overscan>,
+    on_end_reached: Option<(f64, Box<dyn FnMut()>)>,
+    origin: usize,
+    stick_to_end: bool,
+    window_scroll: bool,
+    initial_scroll: i32,
 }
 
 pub struct Builder<F> {
@@ -29,8 +39,9 @@ impl<F> Builder<F> {
         self
     }
 
-    pub fn size(&mut self, size: f64) -> &mut Self {
-        self.inner.as_mut().unwrap().size = size;
+    /// Size of the viewport, or [`Size::Auto`] to measure the mounted element.
+    pub fn size(&mut self, size: impl Into<Size>) -> &mut Self {
+        self.inner.as_mut().unwrap().size = size.into();
         self
     }
 
@@ -39,24 +50,172 @@ impl<F> Builder<F> {
         self
     }
 
+    /// Measure each item as it renders, using `item_size` as an estimate until then.
+    pub fn measure_items(&mut self, measure_items: bool) -> &mut Self {
+        self.inner.as_mut().unwrap().measure_items = measure_items;
+        self
+    }
+
+    /// Overscan before and after the viewport.
+    pub fn overscan(&mut self, overscan: Overscan) -> &mut Self {
+        self.inner.as_mut().unwrap().overscan = overscan;
+        self
+    }
+
+    /// Overscan ahead of the viewport in the current scroll direction,
+    /// or `None` to use [`Builder::overscan`] for both edges.
+    pub fn overscan_ahead(&mut self, overscan: Option<Overscan>) -> &mut Self {
+        self.inner.as_mut().unwrap().overscan_ahead = overscan;
+        self
+    }
+
+    /// Number of items that have been prepended to the list.
+    ///
+    /// Increasing this shifts every index by the number of new items
+    /// and adjusts the scroll offset to keep the same items in view.
+    pub fn origin(&mut self, origin: usize) -> &mut Self {
+        self.inner.as_mut().unwrap().origin = origin;
+        self
+    }
+
+    /// Start scrolled to the end, and stay there as items are appended while at the end.
+    pub fn stick_to_end(&mut self, stick_to_end: bool) -> &mut Self {
+        self.inner.as_mut().unwrap().stick_to_end = stick_to_end;
+        self
+    }
+
+    /// Scroll with the document instead of the mounted element.
+    ///
+    /// The viewport is the window, and the range is computed from the list's position within it.
+    /// This requires the `web` feature, and is ignored otherwise.
+    pub fn window_scroll(&mut self, window_scroll: bool) -> &mut Self {
+        self.inner.as_mut().unwrap().window_scroll = window_scroll;
+        self
+    }
+
+    /// Scroll offset to start at.
+    ///
+    /// The range in view from here is loaded during the first render,
+    /// so it can be rendered on the server before the list is mounted.
+    pub fn initial_scroll(&mut self, initial_scroll: i32) -> &mut Self {
+        self.inner.as_mut().unwrap().initial_scroll = initial_scroll;
+        self
+    }
+
+    /// Call `f` when the viewport gets within `threshold` pixels of the end of the list.
+    ///
+    /// This is called once for each length of the list,
+    /// so it can grow the list (e.g. by fetching another page) before being called again.
+    pub fn on_end_reached(&mut self, threshold: f64, f: impl FnMut() + 'static) -> &mut Self {
+        self.inner.as_mut().unwrap().on_end_reached = Some((threshold, Box::new(f)));
+        self
+    }
+
     pub fn use_list(&mut self, make_value: F) -> UseList<F::Values>
     where
         F: Lazy,
     {
         let mounted = use_mounted();
-        let lazy = make_value.values();
+        let element_size = platform::use_size(mounted);
 
         let inner = self.inner.take().unwrap();
-        let mut lazy_clone = lazy.clone();
+        let window_scroll = inner.window_scroll && cfg!(feature = "web");
+        let direction = inner.direction;
+        let mut window_size = use_signal(|| None);
+        let size = match inner.size {
+            _ if window_scroll => window_size().unwrap_or_default(),
+            Size::Pixels(size) => size,
+            Size::Auto => element_size()
+                .map(|(width, height)| match inner.direction {
+                    Direction::Row => height,
+                    Direction::Column => width,
+                })
+                .unwrap_or_default(),
+        };
+        // The values are created once the initial range is known.
+        let mut lazy_cell = use_hook(|| CopyValue::new(None::<F::Values>));
         let scroll_range = UseScrollRange::builder()
-            .size(inner.size)
+            .direction(inner.direction)
+            .size(size)
             .item_size(inner.item_size)
-            .len(inner.len)
-            .use_scroll_range(move |range| lazy_clone.set(range));
+            .measure_items(inner.measure_items)
+            .overscan(inner.overscan)
+            .overscan_ahead(inner.overscan_ahead)
+            .len(make_value.known_len().unwrap_or(inner.len))
+            .origin(inner.origin)
+            .stick_to_end(inner.stick_to_end)
+            .initial_scroll(inner.initial_scroll)
+            .on_prepend(move |by| {
+                if let Some(mut lazy) = lazy_cell.peek().clone() {
+                    lazy.shift(by)
+                }
+            })
+            .use_scroll_range(move |range| {
+                if let Some(mut lazy) = lazy_cell.peek().clone() {
+                    lazy.set(range)
+                }
+            });
+
+        let lazy = make_value.values_with_initial(scroll_range.range());
+        lazy_cell.set(Some(lazy.clone()));
+
+        // Track the document's scroll offset and the window's size in window scroll mode.
+        let mut scroll_range_clone = scroll_range;
+        platform::use_window_listener(window_scroll, mounted, move || {
+            let size = platform::window_size(direction);
+            if *window_size.peek() != size {
+                window_size.set(size);
+            }
+
+            if let Some(mounted) = mounted.signal.peek().as_deref() {
+                let scroll = platform::window_offset(mounted, direction);
+                if *scroll_range_clone.scroll.peek() != scroll {
+                    scroll_range_clone.set_scroll(scroll);
+                }
+            }
+        });
+
+        // Move the mounted element, or the window, to scroll offsets set by the scroll range.
+        let mut pending_scroll = scroll_range.pending_scroll;
+        use_effect(move || {
+            let Some(scroll) = *pending_scroll.read() else {
+                return;
+            };
+            if let Some(mounted) = mounted.signal.read().as_deref() {
+                if !window_scroll {
+                    platform::sync_scroll(mounted, direction, scroll);
+                } else if platform::window_offset(mounted, direction) != scroll {
+                    let behavior = ScrollBehavior::Instant;
+                    platform::scroll_window_to(mounted, direction, scroll as f64, behavior);
+                }
+                pending_scroll.set(None);
+            }
+        });
+
+        // The handler is replaced on every render, so the effect always calls the latest one.
+        let (threshold, f) = inner.on_end_reached.unzip();
+        let threshold = use_effect_signal(threshold);
+        let mut on_end_reached = use_hook(|| CopyValue::new(None::<Box<dyn FnMut()>>));
+        on_end_reached.set(f);
+
+        let mut reached_len = use_hook(|| CopyValue::new(None));
+        use_effect(move || {
+            let Some(threshold) = *threshold.read() else {
+                return;
+            };
+            let len = *scroll_range.len.read();
+            if scroll_range.remaining() <= threshold && *reached_len.peek() != Some(len) {
+                reached_len.set(Some(len));
+                if let Some(f) = &mut *on_end_reached.write() {
+                    f();
+                }
+            }
+        });
 
         UseList {
             mounted,
             scroll_range,
+            window_scroll,
             lazy,
         }
     }
@@ -65,6 +224,10 @@ impl<F> Builder<F> {
 pub struct UseList<T: 'static> {
     pub mounted: UseMounted,
     pub scroll_range: UseScrollRange,
+
+    /// Whether the list scrolls with the document instead of the mounted element.
+    pub window_scroll: bool,
+
     pub lazy: T,
 }
 
@@ -74,17 +237,94 @@ impl<T> UseList<T> {
             inner: Some(Inner {
                 direction: Direction::Row,
                 len: 0,
-                size: 400.,
+                size: Size::default(),
                 item_size: 20.,
+                measure_items: false,
+                overscan: Overscan::default(),
+                overscan_ahead: None,
+                on_end_reached: None,
+                origin: 0,
+                stick_to_end: false,
+                window_scroll: false,
+                initial_scroll: 0,
             }),
             _marker: PhantomData,
         }
     }
 
     pub fn scroll(&mut self) {
-        if let Some(mounted) = self.mounted.signal.read().as_deref() {
-            let elem = mounted.try_as_web_event().unwrap();
-            self.scroll_range.scroll.set(elem.scroll_top());
+        let Some(mounted) = self.mounted.signal.read().clone() else {
+            return;
+        };
+        let mut scroll_range = self.scroll_range;
+        let direction = scroll_range.direction;
+        if self.window_scroll {
+            scroll_range.set_scroll(platform::window_offset(&mounted, direction));
+            return;
+        }
+
+        platform::read_scroll(mounted, move |left, top| {
+            scroll_range.set_scroll(match direction {
+                Direction::Row => top,
+                Direction::Column => left,
+            })
+        });
+    }
+
+    /// Update the loaded value at `idx` in place with `f`.
+    pub fn update<V>(&mut self, idx: usize, f: impl FnOnce(&mut V))
+    where
+        T: Values<Value = V>,
+    {
+        self.lazy.update(idx, f)
+    }
+
+    /// Insert `value` at `idx`, growing the list by one until its length is next set.
+    pub fn insert<V>(&mut self, idx: usize, value: V)
+    where
+        T: Values<Value = V>,
+    {
+        self.lazy.insert(idx, value);
+        self.scroll_range.insert(idx);
+    }
+
+    /// Remove the value at `idx`, shrinking the list by one until its length is next set.
+    ///
+    /// Returns the removed value if it was loaded.
+    pub fn remove<V>(&mut self, idx: usize) -> Option<V>
+    where
+        T: Values<Value = V>,
+    {
+        let value = self.lazy.remove(idx);
+        self.scroll_range.remove(idx);
+        value
+    }
+
+    /// Scroll the mounted element to bring the item at `idx` into view with `align`.
+    pub fn scroll_to_index(&mut self, idx: usize, align: Align, behavior: ScrollBehavior) {
+        let offset = self.scroll_range.offset_to_index(idx, align);
+        self.scroll_to_offset(offset, behavior)
+    }
+
+    /// Scroll the mounted element to `offset` along the list's direction.
+    ///
+    /// This requires the `web` feature, and only logs a warning otherwise.
+    pub fn scroll_to_offset(&mut self, offset: f64, behavior: ScrollBehavior) {
+        let Some(mounted) = self.mounted.signal.read().clone() else {
+            return;
+        };
+        let direction = self.scroll_range.direction;
+
+        let is_instant = matches!(behavior, ScrollBehavior::Instant);
+        if self.window_scroll {
+            platform::scroll_window_to(&mounted, direction, offset, behavior);
+        } else {
+            platform::scroll_to(&mounted, direction, offset, behavior);
+        }
+
+        // Smooth scrolling updates the range from scroll events as it goes.
+        if is_instant && cfg!(feature = "web") {
+            self.scroll_range.set_scroll(offset as i32);
         }
     }
 }
@@ -94,6 +334,7 @@ impl<T: Clone> Clone for UseList<T> {
         Self {
             mounted: self.mounted,
             scroll_range: self.scroll_range,
+            window_scroll: self.window_scroll,
             lazy: self.lazy.clone(),
         }
     }
@@ -105,6 +346,7 @@ impl<T: PartialEq> PartialEq for UseList<T> {
     fn eq(&self, other: &Self) -> bool {
         self.mounted == other.mounted
             && self.scroll_range == other.scroll_range
+            && self.window_scroll == other.window_scroll
             && self.lazy == other.lazy
     }
 }
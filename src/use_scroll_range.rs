@@ -1,12 +1,35 @@
-use crate::Direction;
+use crate::{Align, Direction, Sizes};
 use dioxus::prelude::*;
 use std::ops::Range;
 
+/// Extra space to render beyond an edge of the viewport.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overscan {
+    /// Number of extra items.
+    Items(usize),
+
+    /// Extra size in pixels.
+    Pixels(f64),
+}
+
+impl Default for Overscan {
+    fn default() -> Self {
+        Self::Items(0)
+    }
+}
+
 struct Inner {
     direction: Direction,
     len: usize,
     size: f64,
     item_size: f64,
+    measure_items: bool,
+    overscan: Overscan,
+    overscan_ahead: Option<Overscan>,
+    origin: usize,
+    stick_to_end: bool,
+    initial_scroll: i32,
+    on_prepend: Option<Box<dyn FnMut(usize)>>,
 }
 
 pub struct Builder {
@@ -34,34 +57,137 @@ impl Builder {
         self
     }
 
+    /// Measure each item as it renders, using `item_size` as an estimate until then.
+    pub fn measure_items(&mut self, measure_items: bool) -> &mut Self {
+        self.inner.as_mut().unwrap().measure_items = measure_items;
+        self
+    }
+
+    /// Overscan before and after the viewport.
+    pub fn overscan(&mut self, overscan: Overscan) -> &mut Self {
+        self.inner.as_mut().unwrap().overscan = overscan;
+        self
+    }
+
+    /// Overscan ahead of the viewport in the current scroll direction,
+    /// or `None` to use [`Builder::overscan`] for both edges.
+    pub fn overscan_ahead(&mut self, overscan: Option<Overscan>) -> &mut Self {
+        self.inner.as_mut().unwrap().overscan_ahead = overscan;
+        self
+    }
+
+    /// Number of items that have been prepended to the list.
+    ///
+    /// Increasing this shifts every index by the number of new items
+    /// and adjusts the scroll offset to keep the same items in view.
+    pub fn origin(&mut self, origin: usize) -> &mut Self {
+        self.inner.as_mut().unwrap().origin = origin;
+        self
+    }
+
+    /// Start scrolled to the end, and stay there as items are appended while at the end.
+    pub fn stick_to_end(&mut self, stick_to_end: bool) -> &mut Self {
+        self.inner.as_mut().unwrap().stick_to_end = stick_to_end;
+        self
+    }
+
+    /// Scroll offset to start at, which also sets the first range to render.
+    pub fn initial_scroll(&mut self, initial_scroll: i32) -> &mut Self {
+        self.inner.as_mut().unwrap().initial_scroll = initial_scroll;
+        self
+    }
+
+    /// Call `f` with the number of prepended items when [`Builder::origin`] increases,
+    /// before the new range is requested.
+    pub fn on_prepend(&mut self, f: impl FnMut(usize) + 'static) -> &mut Self {
+        self.inner.as_mut().unwrap().on_prepend = Some(Box::new(f));
+        self
+    }
+
     pub fn use_scroll_range(
         &mut self,
         mut onscroll: impl FnMut(Range<usize>) + 'static,
     ) -> UseScrollRange {
         let inner = self.inner.take().unwrap();
-        let len = inner.len;
+        let mut len = use_signal(|| inner.len);
+        let mut origin = use_signal(|| inner.origin);
         let size = use_effect_signal(inner.size);
         let item_size = use_effect_signal(inner.item_size);
-        let scroll = use_signal(|| 0);
-
-        use_effect(move || {
-            let item_height = item_size();
-            let top_row = (scroll() as f64 / item_height).floor() as usize;
-            let total_rows = (size() / item_height).floor() as usize + 1;
-            let bottom_row = (top_row + total_rows).min(len);
-            onscroll(top_row..bottom_row)
+        let overscan = use_effect_signal(inner.overscan);
+        let overscan_ahead = use_effect_signal(inner.overscan_ahead);
+        let scroll = use_signal(|| inner.initial_scroll);
+        let pending_scroll =
+            use_signal(|| (inner.initial_scroll != 0).then_some(inner.initial_scroll));
+        let is_forward = use_hook(|| CopyValue::new(true));
+        let sizes = use_signal(|| {
+            let len = if inner.measure_items { inner.len } else { 0 };
+            Sizes::new(len, inner.item_size)
         });
 
-        UseScrollRange {
+        let scroll_range = UseScrollRange {
+            direction: inner.direction,
             scroll,
+            pending_scroll,
             size,
             item_size,
+            sizes: inner.measure_items.then_some(sizes),
+            overscan,
+            overscan_ahead,
+            is_forward,
             len,
-        }
+        };
+
+        // Update the length and origin together so prepends are applied before the new range.
+        // Only the props are tracked, so lengths set by `insert` and `remove` aren't reset.
+        let stick_to_end = inner.stick_to_end;
+        let mut on_prepend = inner.on_prepend;
+        let mut is_first = true;
+        use_effect(use_reactive(
+            (&inner.len, &inner.origin),
+            move |(new_len, new_origin)| {
+                let mut scroll_range = scroll_range;
+                let prepended = new_origin.saturating_sub(*origin.peek());
+                let is_appended = new_len > *len.peek() + prepended;
+                let is_at_end = scroll_range.peek_remaining() <= 1.;
+
+                if let Some(mut sizes) = scroll_range.sizes {
+                    if prepended > 0 || sizes.peek().len() != new_len {
+                        let mut sizes_ref = sizes.write();
+                        sizes_ref.prepend(prepended);
+                        sizes_ref.resize(new_len);
+                    }
+                }
+                if *origin.peek() != new_origin {
+                    origin.set(new_origin);
+                }
+                if *len.peek() != new_len {
+                    len.set(new_len);
+                }
+
+                if prepended > 0 {
+                    if let Some(f) = &mut on_prepend {
+                        f(prepended);
+                    }
+                    let offset = scroll_range.peek_offset(prepended);
+                    let last = *scroll.peek();
+                    scroll_range.scroll_to(last + offset as i32);
+                }
+
+                if stick_to_end && (is_first || (is_at_end && is_appended)) {
+                    let end = scroll_range.peek_inner_size() - *size.peek();
+                    scroll_range.scroll_to(end.max(0.) as i32);
+                }
+                is_first = false;
+            },
+        ));
+
+        use_effect(move || onscroll(scroll_range.range()));
+
+        scroll_range
     }
 }
 
-fn use_effect_signal<V>(value: V) -> Signal<V>
+pub(crate) fn use_effect_signal<V>(value: V) -> Signal<V>
 where
     V: PartialEq + Clone + 'static,
 {
@@ -74,10 +200,21 @@ where
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct UseScrollRange {
+    pub direction: Direction,
     pub scroll: Signal<i32>,
+
+    /// Scroll offset set by [`UseScrollRange::scroll_to`] that the scroll container should be moved to.
+    ///
+    /// This is taken once it's applied, so offsets read from scroll events aren't written back.
+    pub pending_scroll: Signal<Option<i32>>,
+
     pub size: Signal<f64>,
     pub item_size: Signal<f64>,
-    pub len: usize,
+    pub sizes: Option<Signal<Sizes>>,
+    pub overscan: Signal<Overscan>,
+    pub overscan_ahead: Signal<Option<Overscan>>,
+    pub len: Signal<usize>,
+    is_forward: CopyValue<bool>,
 }
 
 impl UseScrollRange {
@@ -88,20 +225,156 @@ impl UseScrollRange {
                 len: 0,
                 size: 400.,
                 item_size: 20.,
+                measure_items: false,
+                overscan: Overscan::default(),
+                overscan_ahead: None,
+                origin: 0,
+                stick_to_end: false,
+                initial_scroll: 0,
+                on_prepend: None,
             }),
         }
     }
 
+    /// Get the offset of the start of the item at `idx`.
+    pub fn offset(&self, idx: usize) -> f64 {
+        match self.sizes {
+            Some(sizes) => sizes.read().offset(idx),
+            None => idx as f64 * *self.item_size.read(),
+        }
+    }
+
+    /// Get the index of the item containing `offset`.
+    pub fn index_at(&self, offset: f64) -> usize {
+        match self.sizes {
+            Some(sizes) => sizes.read().index_at(offset),
+            None => (offset / *self.item_size.read()).floor() as usize,
+        }
+    }
+
+    /// Get the total size of all items.
+    pub fn inner_size(&self) -> f64 {
+        self.offset(*self.len.read())
+    }
+
+    /// Get the remaining size between the end of the viewport and the end of the items.
+    pub fn remaining(&self) -> f64 {
+        self.inner_size() - (*self.scroll.read() as f64 + *self.size.read())
+    }
+
+    /// Get the offset of the start of the item at `idx`, without subscribing to changes.
+    fn peek_offset(&self, idx: usize) -> f64 {
+        match self.sizes {
+            Some(sizes) => sizes.peek().offset(idx),
+            None => idx as f64 * *self.item_size.peek(),
+        }
+    }
+
+    /// Get the total size of all items, without subscribing to changes.
+    fn peek_inner_size(&self) -> f64 {
+        self.peek_offset(*self.len.peek())
+    }
+
+    /// Get the remaining size after the viewport, without subscribing to changes.
+    fn peek_remaining(&self) -> f64 {
+        self.peek_inner_size() - (*self.scroll.peek() as f64 + *self.size.peek())
+    }
+
+    /// Get the scroll offset that brings the item at `idx` into view with `align`.
+    pub fn offset_to_index(&self, idx: usize, align: Align) -> f64 {
+        let scroll = *self.scroll.read() as f64;
+        let size = *self.size.read();
+        let start = self.offset(idx);
+        let end = self.offset(idx + 1);
+
+        let offset = match align {
+            Align::Start => start,
+            Align::Center => start - (size - (end - start)) / 2.,
+            Align::End => end - size,
+            Align::Auto if start < scroll => start,
+            Align::Auto if end > scroll + size => end - size,
+            Align::Auto => scroll,
+        };
+        offset.min(self.inner_size() - size).max(0.)
+    }
+
+    /// Set the current scroll offset, tracking the scroll direction.
+    pub fn set_scroll(&mut self, scroll: i32) {
+        self.is_forward.set(scroll >= *self.scroll.peek());
+        self.scroll.set(scroll);
+    }
+
+    /// Set the current scroll offset and move the scroll container there.
+    pub fn scroll_to(&mut self, scroll: i32) {
+        self.scroll.set(scroll);
+        self.pending_scroll.set(Some(scroll));
+    }
+
+    /// Insert an item at `idx`, until the length is next set.
+    ///
+    /// Items in view stay in place when the item is inserted before them.
+    pub fn insert(&mut self, idx: usize) {
+        let is_before = idx < self.start();
+        if let Some(mut sizes) = self.sizes {
+            sizes.write().insert(idx);
+        }
+        self.len += 1;
+
+        if is_before {
+            let size = self.offset(idx + 1) - self.offset(idx);
+            let last = *self.scroll.peek();
+            self.scroll_to(last + size as i32);
+        }
+    }
+
+    /// Remove the item at `idx`, until the length is next set.
+    ///
+    /// Items in view stay in place when the item is removed from before them.
+    pub fn remove(&mut self, idx: usize) {
+        if idx >= *self.len.peek() {
+            return;
+        }
+        let is_before = idx < self.start();
+        let size = self.offset(idx + 1) - self.offset(idx);
+        if let Some(mut sizes) = self.sizes {
+            sizes.write().remove(idx);
+        }
+        self.len -= 1;
+
+        if is_before {
+            let last = *self.scroll.peek();
+            self.scroll_to((last - size as i32).max(0));
+        }
+    }
+
     /// Get the current start index.
     pub fn start(&self) -> usize {
-        (*self.scroll.read() as f64 / *self.item_size.read()).floor() as usize
+        self.index_at(*self.scroll.read() as f64)
     }
 
-    /// Get the current range of item indices.
+    /// Get the current range of item indices, including overscan.
     pub fn range(&self) -> Range<usize> {
-        let start = self.start();
-        let total = (*self.size.read() / *self.item_size.read()).floor() as usize + 1;
-        let end = (start + total).min(self.len);
-        start..end
+        let scroll = *self.scroll.read() as f64;
+        let size = *self.size.read();
+
+        let behind = *self.overscan.read();
+        let ahead = self.overscan_ahead.read().unwrap_or(behind);
+        let (before, after) = if *self.is_forward.read() {
+            (behind, ahead)
+        } else {
+            (ahead, behind)
+        };
+
+        let start = match before {
+            Overscan::Items(items) => self.index_at(scroll).saturating_sub(items),
+            Overscan::Pixels(pixels) => self.index_at((scroll - pixels).max(0.)),
+        };
+        let end = match after {
+            Overscan::Items(items) => self.index_at(scroll + size) + 1 + items,
+            Overscan::Pixels(pixels) => self.index_at(scroll + size + pixels) + 1,
+        };
+        // The length can shrink below the scroll offset, so keep the range from inverting.
+        let end = end.min(*self.len.read());
+        start.min(end)..end
     }
 }